@@ -0,0 +1,48 @@
+//! Compares the parallel alpha-beta search against the sequential one on encounters
+//! with several members per side, where the root offers many actions to share.
+//!
+//! Run with `cargo bench --bench parallel`.
+
+use autobattler::{Personality, Scenario, Solver, SolverStrategy, WeaponCatalog};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::path::Path;
+
+/// The multi-member scenarios to search, along with their search depth.
+const SCENARIOS: [(&str, usize); 3] = [("formation", 10), ("skirmish", 10), ("spells", 10)];
+
+/// The thread counts to compare.
+const THREADS: [usize; 4] = [1, 2, 4, 8];
+
+fn parallel_search(c: &mut Criterion) {
+    let catalog = WeaponCatalog::builtin();
+    for (name, max_depth) in SCENARIOS {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("scenarios")
+            .join(name)
+            .with_extension("toml");
+        let scenario = Scenario::load(&path, &catalog).expect("The scenario is valid");
+
+        let mut group = c.benchmark_group(name);
+        group.sample_size(10);
+        for threads in THREADS {
+            group.bench_with_input(
+                BenchmarkId::new("threads", threads),
+                &threads,
+                |b, &threads| {
+                    b.iter(|| {
+                        Solver::engage_parallel(
+                            &scenario.conflict,
+                            SolverStrategy::IterativeDeepening(max_depth),
+                            &Personality::default(),
+                            threads,
+                        )
+                    })
+                },
+            );
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, parallel_search);
+criterion_main!(benches);
@@ -1,17 +1,42 @@
 use crate::party::Participant;
-use crate::weapon::Weapon;
+use crate::status_effect::StatusEffect;
+use crate::weapon::{Weapon, WeaponArea, WeaponRange};
+use serde::Serialize;
 use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum AppliedAction {
     /// The party retreats from the conflict.
     Flee,
+    /// The party or member is unable to act, e.g. when stunned, and passes the turn.
+    Pass,
     /// A party member targets another party member.
     Targeted(TargetedAction),
+    /// A party member targets several members of a party at once.
+    Area(AreaAction),
+    /// A party member changes position, moving to the other row of the party's formation.
+    Move(Participant),
+    /// A party member attempts to get away from the conflict on their own.
+    Escape(EscapeAction),
+}
+
+/// An attempt of a single member to leave the conflict, see
+/// [`Party::escape_chance`](crate::party::Party::escape_chance).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EscapeAction {
+    /// The fleeing member.
+    pub member: Participant,
+    /// The probability of getting away.
+    pub chance: f32,
+    /// Whether the member got away. Members who fail to get away lose their turn.
+    /// Escapes that are yet to be rolled succeed.
+    pub escaped: bool,
 }
 
 /// An applied action.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
 pub struct TargetedAction {
     /// The action.
     pub action: Action,
@@ -19,28 +44,507 @@ pub struct TargetedAction {
     pub source: Participant,
     /// The target of the action.
     pub target: Participant,
+    /// The resources the source spends on the action.
+    pub cost: Cost,
+}
+
+/// An applied action affecting several members of a party at once,
+/// e.g. a fireball. Attacks are rolled once for all targets.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
+pub struct AreaAction {
+    /// The action.
+    pub action: Action,
+    /// The source of the action.
+    pub source: Participant,
+    /// The targets of the action, ordered by member ID.
+    pub targets: Vec<Participant>,
+    /// The resources the source spends on the action.
+    pub cost: Cost,
 }
 
 /// An action to be taken.
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum Action {
     /// Performs a simple attack.
     SimpleAttack(SimpleAttackAction),
+    /// Restores the health of an ally.
+    Heal(HealAction),
+    /// Strengthens the attacks of an ally.
+    Buff(BuffAction),
+    /// Applies a status effect, e.g. poisons an opponent or lets an ally regenerate.
+    StatusEffect(StatusEffect),
+    /// Casts a spell, applying its effect to every target.
+    Spell(SpellAction),
+    /// Takes a defensive stance, reducing the damage taken until the member's next turn.
+    Defend(DefendAction),
+    /// Protects an ally until the member's next turn: attacks on the ally hit the
+    /// guarding member instead.
+    Guard,
+}
+
+/// An action a member is able to take, along with its cost.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Ability {
+    /// The action.
+    pub action: Action,
+    /// The resources spent on every use of the action.
+    pub cost: Cost,
+}
+
+/// The resources spent on an action. Members can only take actions they can afford.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize)]
+pub struct Cost {
+    /// The mana spent, e.g. on spells.
+    pub mana: f32,
+    /// The stamina spent, e.g. on heavy swings.
+    pub stamina: f32,
 }
 
 /// A simple attack.
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize)]
 pub struct SimpleAttackAction {
     /// The weapon used for the attack.
     pub weapon: Option<Weapon>,
     /// The damage inflicted on the selected target.
     pub damage: f32,
+    /// How the attack landed. Attacks that are yet to be rolled are hits.
+    pub roll: AttackRoll,
+}
+
+/// A spell being cast, see [`Spell`](crate::spell::Spell).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SpellAction {
+    /// The name of the spell.
+    pub name: String,
+    /// The effect of the spell on each of its targets.
+    pub effect: Box<Action>,
+    /// The members affected by the spell.
+    pub area: WeaponArea,
+}
+
+/// A heal restoring health of an ally, up to their maximum health.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealAction {
+    /// The amount of health restored.
+    pub amount: f32,
+}
+
+/// A buff increasing the damage of an ally's weapon attacks.
+/// Buffs do not stack; a stronger buff replaces a weaker one.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BuffAction {
+    /// The damage added to each weapon attack.
+    pub damage_bonus: f32,
+}
+
+/// A defensive stance reducing the damage the member takes until their next turn.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DefendAction {
+    /// The factor applied to incoming damage, e.g. `0.5` to halve it.
+    pub damage_multiplier: f32,
+}
+
+/// How an attack landed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttackRoll {
+    /// The attack hit for regular damage.
+    Hit,
+    /// The attack hit critically for double damage.
+    Critical,
+    /// The attack missed and dealt no damage.
+    Miss,
+}
+
+impl AppliedAction {
+    /// Enumerates the possible results of the action along with their probabilities,
+    /// which sum up to one. Deterministic actions have exactly one result.
+    pub fn outcomes(&self) -> Vec<(f32, AppliedAction)> {
+        match self {
+            AppliedAction::Flee | AppliedAction::Pass | AppliedAction::Move(_) => {
+                vec![(1.0, self.clone())]
+            }
+            AppliedAction::Escape(escape) => escape
+                .outcomes()
+                .into_iter()
+                .map(|(probability, escape)| (probability, AppliedAction::Escape(escape)))
+                .collect(),
+            AppliedAction::Targeted(targeted) => targeted
+                .action
+                .outcomes()
+                .into_iter()
+                .map(|(probability, action)| {
+                    let action = TargetedAction {
+                        action,
+                        ..targeted.clone()
+                    };
+                    (probability, AppliedAction::Targeted(action))
+                })
+                .collect(),
+            AppliedAction::Area(area) => area
+                .action
+                .outcomes()
+                .into_iter()
+                .map(|(probability, action)| {
+                    let action = AreaAction {
+                        action,
+                        ..area.clone()
+                    };
+                    (probability, AppliedAction::Area(action))
+                })
+                .collect(),
+        }
+    }
+
+    /// Determines whether the action may have a result, i.e. whether the result
+    /// is the action itself or one of its random [`outcomes`](AppliedAction::outcomes).
+    pub fn has_outcome(&self, result: &AppliedAction) -> bool {
+        self == result || self.outcomes().iter().any(|(_, outcome)| outcome == result)
+    }
+
+    /// Gets the members targeted by the action, if any.
+    pub fn targets(&self) -> &[Participant] {
+        match self {
+            AppliedAction::Flee
+            | AppliedAction::Pass
+            | AppliedAction::Move(_)
+            | AppliedAction::Escape(_) => &[],
+            AppliedAction::Targeted(targeted) => std::slice::from_ref(&targeted.target),
+            AppliedAction::Area(area) => &area.targets,
+        }
+    }
+
+    /// Selects the most likely result of the action; see [`AppliedAction::most_likely`].
+    pub fn most_likely_outcome(&self) -> (f32, AppliedAction) {
+        let mut outcomes = self.outcomes();
+        let index = Self::most_likely(&outcomes);
+        outcomes.swap_remove(index)
+    }
+
+    /// Selects the most likely of the results of an action, preferring results of the
+    /// most likely kind of roll: an attack that hits three out of four times most likely
+    /// hits, even if every single damage roll is less likely than a miss.
+    ///
+    /// ## Returns
+    /// The index of the selected result.
+    pub fn most_likely(outcomes: &[(f32, AppliedAction)]) -> usize {
+        let roll_probability = |roll: Option<AttackRoll>| -> f32 {
+            outcomes
+                .iter()
+                .filter(|(_, action)| action.roll() == roll)
+                .map(|(probability, _)| probability)
+                .sum()
+        };
+
+        let mut best = 0;
+        let mut best_rank = (f32::NEG_INFINITY, f32::NEG_INFINITY);
+        for (index, (probability, action)) in outcomes.iter().enumerate() {
+            let rank = (roll_probability(action.roll()), *probability);
+            if rank > best_rank {
+                best = index;
+                best_rank = rank;
+            }
+        }
+
+        best
+    }
+
+    /// Gets how an attack landed, if the action is an attack.
+    fn roll(&self) -> Option<AttackRoll> {
+        let action = match self {
+            AppliedAction::Flee
+            | AppliedAction::Pass
+            | AppliedAction::Move(_)
+            | AppliedAction::Escape(_) => return None,
+            AppliedAction::Targeted(targeted) => &targeted.action,
+            AppliedAction::Area(area) => &area.action,
+        };
+
+        match action.effect() {
+            Action::SimpleAttack(attack) => Some(attack.roll),
+            _ => None,
+        }
+    }
+}
+
+impl Action {
+    /// Enumerates the possible results of the action along with their probabilities.
+    /// Only attacks, including attacking spells, have more than one result.
+    pub fn outcomes(&self) -> Vec<(f32, Action)> {
+        match self {
+            Action::SimpleAttack(attack) => attack
+                .outcomes()
+                .into_iter()
+                .map(|(probability, attack)| (probability, Action::SimpleAttack(attack)))
+                .collect(),
+            Action::Spell(spell) => spell
+                .effect
+                .outcomes()
+                .into_iter()
+                .map(|(probability, effect)| {
+                    let spell = SpellAction {
+                        effect: Box::new(effect),
+                        ..spell.clone()
+                    };
+                    (probability, Action::Spell(spell))
+                })
+                .collect(),
+            Action::Heal(_)
+            | Action::Buff(_)
+            | Action::StatusEffect(_)
+            | Action::Defend(_)
+            | Action::Guard => vec![(1.0, self.clone())],
+        }
+    }
+
+    /// Gets the effect of the action on its targets, i.e. the effect of a spell
+    /// or the action itself.
+    pub fn effect(&self) -> &Action {
+        match self {
+            Action::Spell(spell) => spell.effect.effect(),
+            _ => self,
+        }
+    }
+
+    /// Gets the highest damage the action can inflict.
+    pub fn max_damage(&self) -> f32 {
+        match self {
+            Action::SimpleAttack(attack) => attack.max_damage(),
+            Action::Spell(spell) => spell.effect.max_damage(),
+            Action::Heal(_)
+            | Action::Buff(_)
+            | Action::StatusEffect(_)
+            | Action::Defend(_)
+            | Action::Guard => 0.0,
+        }
+    }
+
+    /// Gets the members hit by a single use of the action.
+    pub fn area(&self) -> WeaponArea {
+        match self {
+            Action::SimpleAttack(SimpleAttackAction {
+                weapon: Some(weapon),
+                ..
+            }) => weapon.area,
+            Action::Spell(spell) => spell.area,
+            Action::SimpleAttack(_)
+            | Action::Heal(_)
+            | Action::Buff(_)
+            | Action::StatusEffect(_)
+            | Action::Defend(_)
+            | Action::Guard => WeaponArea::Single,
+        }
+    }
+
+    /// Determines whether the action is a melee weapon attack, which only reaches the front
+    /// row of a formation. Ranged attacks and spells reach every member.
+    pub fn is_melee(&self) -> bool {
+        match self {
+            Action::SimpleAttack(attack) => attack
+                .weapon
+                .as_ref()
+                .is_some_and(|weapon| weapon.range == WeaponRange::Melee),
+            _ => false,
+        }
+    }
+
+    /// Determines whether the action targets members of the acting party
+    /// rather than their opponents.
+    pub fn targets_allies(&self) -> bool {
+        match self {
+            Action::SimpleAttack(_) => false,
+            Action::Heal(_) | Action::Buff(_) | Action::Defend(_) | Action::Guard => true,
+            Action::StatusEffect(effect) => effect.is_beneficial(),
+            Action::Spell(spell) => spell.effect.targets_allies(),
+        }
+    }
+}
+
+/// Escapes compare equal if all of their fields are equal;
+/// chances are never `NaN`.
+impl Eq for EscapeAction {}
+
+impl Hash for EscapeAction {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.member.hash(state);
+        self.chance.to_bits().hash(state);
+        self.escaped.hash(state);
+    }
+}
+
+/// Actions compare equal if all of their fields are equal;
+/// amounts are never `NaN`.
+impl Eq for Action {}
+
+impl Hash for Action {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Action::SimpleAttack(attack) => {
+                attack.weapon.hash(state);
+                attack.damage.to_bits().hash(state);
+                attack.roll.hash(state);
+            }
+            Action::Heal(heal) => heal.amount.to_bits().hash(state),
+            Action::Buff(buff) => buff.damage_bonus.to_bits().hash(state),
+            Action::StatusEffect(effect) => effect.hash(state),
+            Action::Spell(spell) => {
+                spell.name.hash(state);
+                spell.effect.hash(state);
+                spell.area.hash(state);
+            }
+            Action::Defend(defend) => defend.damage_multiplier.to_bits().hash(state),
+            Action::Guard => {}
+        }
+    }
+}
+
+/// Actions without a cost are free abilities.
+impl From<Action> for Ability {
+    fn from(action: Action) -> Self {
+        Self {
+            action,
+            cost: Cost::default(),
+        }
+    }
+}
+
+/// Abilities compare equal if all of their fields are equal;
+/// amounts are never `NaN`.
+impl Eq for Ability {}
+
+impl Hash for Ability {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.action.hash(state);
+        self.cost.hash(state);
+    }
+}
+
+impl Cost {
+    /// Determines whether the action can be taken without spending any resources.
+    pub fn is_free(&self) -> bool {
+        self.mana <= 0.0 && self.stamina <= 0.0
+    }
+}
+
+/// Costs compare equal if all of their fields are equal;
+/// amounts are never `NaN`.
+impl Eq for Cost {}
+
+impl Hash for Cost {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.mana.to_bits().hash(state);
+        self.stamina.to_bits().hash(state);
+    }
+}
+
+/// Describes the cost, e.g. "3 mana and 1 stamina".
+impl Display for Cost {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::default();
+        if self.mana > 0.0 {
+            parts.push(format!("{} mana", self.mana));
+        }
+        if self.stamina > 0.0 {
+            parts.push(format!("{} stamina", self.stamina));
+        }
+        if parts.is_empty() {
+            parts.push("nothing".to_string());
+        }
+        write!(f, "{}", parts.join(" and "))
+    }
+}
+
+impl EscapeAction {
+    /// Enumerates the possible results of the escape along with their probabilities,
+    /// getting away first.
+    pub fn outcomes(&self) -> Vec<(f32, EscapeAction)> {
+        let chance = self.chance.clamp(0.0, 1.0);
+        if chance >= 1.0 {
+            return vec![(1.0, self.clone())];
+        }
+
+        let rolled = |escaped| EscapeAction {
+            escaped,
+            ..self.clone()
+        };
+        vec![(chance, rolled(true)), (1.0 - chance, rolled(false))]
+    }
+}
+
+impl SimpleAttackAction {
+    /// Enumerates the possible results of the attack along with their probabilities.
+    /// Hits are listed first, misses last. Attacks without a weapon always hit.
+    pub fn outcomes(&self) -> Vec<(f32, SimpleAttackAction)> {
+        let weapon = match &self.weapon {
+            Some(weapon) if !weapon.is_deterministic() => weapon,
+            _ => return vec![(1.0, self.clone())],
+        };
+
+        let hit_chance = weapon.hit_chance.clamp(0.0, 1.0);
+        let critical_chance = weapon.critical_chance.clamp(0.0, 1.0);
+
+        let mut outcomes = Vec::default();
+        for (roll, probability) in weapon.dice.distribution() {
+            let damage = self.damage + roll as f32;
+            let hit = hit_chance * (1.0 - critical_chance) * probability;
+            if hit > 0.0 {
+                outcomes.push((hit, self.rolled(AttackRoll::Hit, damage)));
+            }
+
+            let critical = hit_chance * critical_chance * probability;
+            if critical > 0.0 {
+                outcomes.push((critical, self.rolled(AttackRoll::Critical, 2.0 * damage)));
+            }
+        }
+
+        if hit_chance < 1.0 {
+            outcomes.push((1.0 - hit_chance, self.rolled(AttackRoll::Miss, 0.0)));
+        }
+
+        outcomes
+    }
+
+    /// Gets the highest damage the attack can inflict.
+    pub fn max_damage(&self) -> f32 {
+        match &self.weapon {
+            Some(weapon) if !weapon.is_deterministic() => {
+                let damage = self.damage + weapon.dice.max() as f32;
+                if weapon.critical_chance > 0.0 {
+                    2.0 * damage
+                } else {
+                    damage
+                }
+            }
+            _ => self.damage,
+        }
+    }
+
+    /// Creates a copy of the attack with a rolled result.
+    fn rolled(&self, roll: AttackRoll, damage: f32) -> Self {
+        Self {
+            weapon: self.weapon.clone(),
+            damage,
+            roll,
+        }
+    }
 }
 
 impl Debug for Action {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Action::SimpleAttack(attack) => write!(f, "attack with {:?}", attack),
+            Action::Heal(heal) => write!(f, "heal for {} health", heal.amount),
+            Action::Buff(buff) => write!(f, "buff for {} damage", buff.damage_bonus),
+            Action::StatusEffect(effect) => write!(f, "{:?}", effect),
+            Action::Spell(spell) => write!(f, "cast {}", spell.name),
+            Action::Defend(defend) => write!(
+                f,
+                "defend, taking {} times the damage",
+                defend.damage_multiplier
+            ),
+            Action::Guard => write!(f, "guard an ally"),
         }
     }
 }
@@ -58,11 +562,126 @@ impl Display for AppliedAction {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             AppliedAction::Flee => write!(f, "the party retreats"),
+            AppliedAction::Pass => write!(f, "the party passes"),
+            AppliedAction::Move(member) => write!(f, "{member} changes position"),
+            AppliedAction::Escape(escape) if escape.escaped => {
+                write!(f, "{} gets away", escape.member)
+            }
+            AppliedAction::Escape(escape) => write!(f, "{} fails to get away", escape.member),
             AppliedAction::Targeted(action) => match action.action {
-                Action::SimpleAttack(_) => {
-                    write!(f, "{} attacks {}", action.source, action.target)
+                Action::SimpleAttack(ref attack) => match attack.roll {
+                    AttackRoll::Hit => write!(f, "{} attacks {}", action.source, action.target),
+                    AttackRoll::Critical => {
+                        write!(f, "{} critically hits {}", action.source, action.target)
+                    }
+                    AttackRoll::Miss => write!(f, "{} misses {}", action.source, action.target),
+                },
+                Action::Heal(_) => write!(f, "{} heals {}", action.source, action.target),
+                Action::Buff(_) => write!(f, "{} strengthens {}", action.source, action.target),
+                Action::StatusEffect(_) => {
+                    write!(f, "{} affects {}", action.source, action.target)
                 }
+                Action::Spell(ref spell) => {
+                    write!(
+                        f,
+                        "{} casts {} on {}",
+                        action.source, spell.name, action.target
+                    )
+                }
+                Action::Defend(_) => write!(f, "{} defends", action.source),
+                Action::Guard => write!(f, "{} guards {}", action.source, action.target),
             },
+            AppliedAction::Area(action) => {
+                let targets: Vec<_> = action.targets.iter().map(|t| t.to_string()).collect();
+                let targets = targets.join(", ");
+                match action.action {
+                    Action::SimpleAttack(ref attack) => match attack.roll {
+                        AttackRoll::Hit => write!(f, "{} attacks {}", action.source, targets),
+                        AttackRoll::Critical => {
+                            write!(f, "{} critically hits {}", action.source, targets)
+                        }
+                        AttackRoll::Miss => write!(f, "{} misses {}", action.source, targets),
+                    },
+                    Action::Heal(_) => write!(f, "{} heals {}", action.source, targets),
+                    Action::Buff(_) => write!(f, "{} strengthens {}", action.source, targets),
+                    Action::StatusEffect(_) => write!(f, "{} affects {}", action.source, targets),
+                    Action::Spell(ref spell) => {
+                        write!(f, "{} casts {} on {}", action.source, spell.name, targets)
+                    }
+                    Action::Defend(_) => write!(f, "{} defends", action.source),
+                    Action::Guard => write!(f, "{} guards {}", action.source, targets),
+                }
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weapon::Dice;
+
+    fn attack(weapon: Weapon) -> SimpleAttackAction {
+        SimpleAttackAction {
+            damage: weapon.damage,
+            weapon: Some(weapon),
+            roll: AttackRoll::Hit,
+        }
+    }
+
+    #[test]
+    fn deterministic_attacks_have_one_outcome() {
+        let attack = attack(Weapon {
+            name: "a stick".to_string(),
+            damage: 10.0,
+            ..Default::default()
+        });
+        assert_eq!(attack.outcomes(), vec![(1.0, attack.clone())]);
+        assert_eq!(attack.max_damage(), 10.0);
+    }
+
+    #[test]
+    fn stochastic_attacks_enumerate_outcomes() {
+        let attack = attack(Weapon {
+            name: "a stick".to_string(),
+            damage: 2.0,
+            dice: Dice { count: 1, sides: 4 },
+            hit_chance: 0.8,
+            critical_chance: 0.25,
+            ..Default::default()
+        });
+
+        let outcomes = attack.outcomes();
+        assert_eq!(outcomes.len(), 9);
+        let total: f32 = outcomes.iter().map(|(p, _)| p).sum();
+        assert!((total - 1.0).abs() < 1e-6);
+
+        let (probability, miss) = outcomes.last().unwrap();
+        assert!((probability - 0.2).abs() < 1e-6);
+        assert_eq!((miss.roll, miss.damage), (AttackRoll::Miss, 0.0));
+
+        let (_, critical) = &outcomes[1];
+        assert_eq!(
+            (critical.roll, critical.damage),
+            (AttackRoll::Critical, 6.0)
+        );
+        assert_eq!(attack.max_damage(), 12.0);
+
+        // A hit is more likely than a miss, even though every single hit is not.
+        let action = AppliedAction::Targeted(TargetedAction {
+            action: Action::SimpleAttack(attack),
+            source: Participant {
+                party_id: 0,
+                member_id: 0,
+            },
+            target: Participant {
+                party_id: 1,
+                member_id: 0,
+            },
+            cost: Cost::default(),
+        });
+        let (probability, action) = action.most_likely_outcome();
+        assert!((probability - 0.15).abs() < 1e-6);
+        assert_eq!(action.roll(), Some(AttackRoll::Hit));
+    }
+}
@@ -1,74 +1,144 @@
-use crate::action::{Action, AppliedAction, TargetedAction};
+use crate::action::{Ability, Action, AppliedAction, AreaAction, EscapeAction, TargetedAction};
 use crate::party::{Participant, Party};
 use crate::party_member::{AttackIterator, PartyMember};
+use crate::weapon::WeaponArea;
+use std::iter::once;
 use std::ops::Range;
 
 /// An action iterator.
 ///
-/// The iterator produces all permutations of party member attack actions
-/// targeting each opponent. Actions are generated for the first party member,
-/// with the first action applied to each individual opponent, then the second
-/// action applied to each opponent, etc. If all actions are exhausted for all opponents,
-/// the next party member is selected and the process repeats.
+/// The iterator produces all permutations of party member actions
+/// targeting each member of all opposing parties, or each ally for supporting actions
+/// such as heals.
+/// Actions hitting an area target each distinct group of members instead.
+/// Actions are generated for the first party member, with the first action applied
+/// to each individual target, then the second action applied to each target, etc.
+/// If all actions are exhausted for all targets, the next party member is selected
+/// and the process repeats.
 #[derive(Debug, Clone)]
 pub struct ActionIterator {
     /// The party whose turn it is.
     current: Party,
-    /// The opponent's party.
-    opponent: Party,
+    /// The other parties of the current party's alliance.
+    allies: Vec<Party>,
+    /// All parties outside the current party's alliance.
+    opponents: Vec<Party>,
     /// The index of the currently active member.
     current_index: usize,
     /// The index range to address in the current party.
     current_range: Range<usize>,
     /// Determines whether the retreat action was already emitted.
     tried_retreat: bool,
-    /// The iterator used to generated actions targeting an enemy party member..
+    /// Determines whether the pass action was already emitted.
+    tried_pass: bool,
+    /// Determines whether members may change rows of their formation.
+    formations: bool,
+    /// Determines whether the move action of the currently active member was already emitted.
+    tried_move: bool,
+    /// Determines whether the escape attempt of the currently active member was already emitted.
+    tried_escape: bool,
+    /// The iterator used to generate actions targeting an enemy or allied party member.
     iter: Option<ActionTargetIterator>,
+    /// The last area action generated, used to skip groups that were already targeted.
+    last_area: Option<AppliedAction>,
+    /// An action to emit before all others, e.g. the best action of a previous search.
+    preferred: Option<AppliedAction>,
+    /// Determines whether the preferred action was already emitted.
+    tried_preferred: bool,
 }
 
-/// An iterator for attack actions of a single player.
+/// An iterator for the actions of a single player.
 ///
-/// The iterator generates all permutations of actions and opponents,
-/// emitting the same action for each opponent first, then producing the next
-/// action for each opponent, etc.
+/// The iterator generates all permutations of actions and targets,
+/// emitting the same action for each target first, then producing the next
+/// action for each target, etc. Actions target either all opponents or all
+/// allies, see [`Action::targets_allies`].
 #[derive(Debug, Clone)]
 struct ActionTargetIterator {
-    /// The current enemy being targeted.
-    enemy_index: usize,
-    /// The range of party member indices in the party member list.
+    /// The current member being targeted.
+    target_index: usize,
+    /// The range of member indices targeted by the current action.
+    targets: Range<usize>,
+    /// The range of party member indices in the opponents' member list.
     enemies: Range<usize>,
+    /// The range of party member indices in the allies' member list, own members first.
+    allies: Range<usize>,
     /// The iterator used to generate the actions.
     iter: Option<AttackIterator>,
     /// The last action produced by the [`iter`].
-    action: Option<Action>,
+    action: Option<Ability>,
 }
 
 impl ActionIterator {
     /// Creates a new iterator selecting all current party members,
-    /// targeting all enemy party members.
-    pub fn new(current: Party, opponent: Party) -> Self {
+    /// targeting all members of the allied or opposing parties.
+    pub fn new(current: Party, allies: Vec<Party>, opponents: Vec<Party>) -> Self {
         let range = 0..current.len();
-        Self::new_in(current, opponent, range)
+        Self::new_in(current, allies, opponents, range)
     }
 
     /// Creates a new iterator selecting only a range of current party members,
-    /// targeting all enemy party members.
-    pub const fn new_in(current: Party, opponent: Party, current_range: Range<usize>) -> Self {
+    /// targeting all members of the allied or opposing parties.
+    pub const fn new_in(
+        current: Party,
+        allies: Vec<Party>,
+        opponents: Vec<Party>,
+        current_range: Range<usize>,
+    ) -> Self {
         Self {
             current,
-            opponent,
+            allies,
+            opponents,
             current_index: current_range.start,
             current_range,
             iter: None,
+            last_area: None,
             tried_retreat: false,
+            tried_pass: false,
+            formations: false,
+            tried_move: false,
+            tried_escape: false,
+            preferred: None,
+            tried_preferred: false,
         }
     }
+
+    /// Emits the specified action first. The action is skipped when it is
+    /// generated later on, so it is produced only once.
+    ///
+    /// The caller must ensure that the action is valid for the current state.
+    pub fn with_preferred(mut self, action: Option<AppliedAction>) -> Self {
+        self.preferred = action;
+        self
+    }
+
+    /// Gets the chance of the members to get away on their own, if they may try.
+    fn escape_chance(&self) -> Option<f32> {
+        self.current
+            .escape_chance
+            .filter(|_| self.current.can_retreat())
+            .filter(|_| self.opponents.iter().any(Party::is_engaged))
+    }
+
+    /// Lets every member that is able to act change rows of the party's formation,
+    /// once all other actions of the member were emitted.
+    pub fn with_formations(mut self, formations: bool) -> Self {
+        self.formations = formations;
+        self
+    }
 }
 
 impl Iterator for ActionIterator {
     type Item = AppliedAction;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if !self.tried_preferred {
+            self.tried_preferred = true;
+            if self.preferred.is_some() {
+                return self.preferred.clone();
+            }
+        }
+
         loop {
             // If the end of the enumeration was reached, we can exit.
             if self.current_index >= self.current_range.end {
@@ -76,17 +146,33 @@ impl Iterator for ActionIterator {
                     self.tried_retreat = true;
                     // TODO: Ensure that not every party can retreat.
 
-                    // No point in running away if the opponent is already running
+                    // No point in running away if all opponents are already running
                     // or defeated. Likewise, ensure we can perform an action at all.
+                    // Parties whose members escape on their own never retreat as a whole.
                     if self.current.can_act()
                         && self.current.can_retreat()
-                        && !self.opponent.has_retreated()
-                        && !self.opponent.is_defeated()
+                        && self.current.escape_chance.is_none()
+                        && self.opponents.iter().any(Party::is_engaged)
+                        && self.preferred != Some(AppliedAction::Flee)
                     {
                         return Some(AppliedAction::Flee);
                     }
                 }
 
+                if !self.tried_pass {
+                    self.tried_pass = true;
+
+                    // Members that are unable to act, e.g. because they are stunned,
+                    // still take their turn so that their status effects wear off.
+                    let members = &self.current.members[self.current_range.clone()];
+                    if members.iter().any(PartyMember::takes_turns)
+                        && !members.iter().any(PartyMember::can_act)
+                        && self.preferred != Some(AppliedAction::Pass)
+                    {
+                        return Some(AppliedAction::Pass);
+                    }
+                }
+
                 return None;
             }
 
@@ -98,21 +184,62 @@ impl Iterator for ActionIterator {
 
             if self.iter.is_none() {
                 let member = self.current.members[self.current_index].clone();
-                let target_range = 0..self.opponent.members.len();
-                self.iter = Some(ActionTargetIterator::new(member, target_range));
+                let enemies = 0..self.opponents.iter().map(Party::len).sum();
+                let allies =
+                    0..self.current.len() + self.allies.iter().map(Party::len).sum::<usize>();
+                self.iter = Some(ActionTargetIterator::new(member, enemies, allies));
             }
 
-            match self.iter.as_mut().map_or(None, |i| i.next()) {
+            match self.iter.as_mut().and_then(|i| i.next()) {
                 None => {
+                    // Changing rows makes no difference to a member standing alone.
+                    let standing = self.current.members.iter().filter(|m| !m.is_dead());
+                    if self.formations && !self.tried_move && standing.count() > 1 {
+                        self.tried_move = true;
+                        let action = AppliedAction::Move(Participant {
+                            party_id: self.current.id,
+                            member_id: self.current.members[self.current_index].id,
+                        });
+
+                        if self.preferred.as_ref() != Some(&action) {
+                            return Some(action);
+                        }
+                    }
+
+                    if !self.tried_escape {
+                        self.tried_escape = true;
+                        if let Some(chance) = self.escape_chance() {
+                            let action = AppliedAction::Escape(EscapeAction {
+                                member: Participant {
+                                    party_id: self.current.id,
+                                    member_id: self.current.members[self.current_index].id,
+                                },
+                                chance,
+                                escaped: true,
+                            });
+
+                            if self.preferred.as_ref() != Some(&action) {
+                                return Some(action);
+                            }
+                        }
+                    }
+
                     // The iterator was exhausted, so we continue with the next member.
                     self.current_index += 1;
                     self.iter = None;
+                    self.tried_move = false;
+                    self.tried_escape = false;
                 }
-                Some((action, target_index)) => {
-                    let opponent = &self.opponent.members[target_index];
+                Some((Ability { action, cost }, target_index)) => {
+                    let (target_party, target_index) = if action.targets_allies() {
+                        locate(once(&self.current).chain(&self.allies), target_index)
+                    } else {
+                        locate(&self.opponents, target_index)
+                    };
+                    let target_member = &target_party.members[target_index];
 
                     // TODO: Rework action generation - should only generate applicable actions to begin with.
-                    if !opponent.is_applicable(&action) {
+                    if !target_member.is_applicable(&action) {
                         continue;
                     }
 
@@ -121,55 +248,146 @@ impl Iterator for ActionIterator {
                         member_id: self.current.members[self.current_index].id,
                     };
 
-                    let target = Participant {
-                        party_id: self.opponent.id,
-                        member_id: opponent.id,
+                    // Members defend themselves and guard others.
+                    let targets_self =
+                        target_party.id == source.party_id && target_member.id == source.member_id;
+                    match action.effect() {
+                        Action::Defend(_) if !targets_self => continue,
+                        Action::Guard if targets_self => continue,
+                        _ => {}
+                    }
+
+                    // Melee attacks cannot reach past the front row.
+                    let is_reachable =
+                        |member| !action.is_melee() || target_party.is_within_reach(member);
+                    if !is_reachable(target_member) {
+                        continue;
+                    }
+
+                    let group = match action.area() {
+                        WeaponArea::Single => None,
+                        WeaponArea::Cleave => Some(
+                            target_index.saturating_sub(1)
+                                ..(target_index + 2).min(target_party.members.len()),
+                        ),
+                        WeaponArea::All => Some(0..target_party.members.len()),
+                    };
+
+                    let action = match group {
+                        None => {
+                            let target = Participant {
+                                party_id: target_party.id,
+                                member_id: target_member.id,
+                            };
+
+                            AppliedAction::Targeted(TargetedAction {
+                                action,
+                                source,
+                                target,
+                                cost,
+                            })
+                        }
+                        Some(group) => {
+                            let targets = target_party.members[group]
+                                .iter()
+                                .filter(|member| member.is_applicable(&action))
+                                .filter(|member| is_reachable(member))
+                                .map(|member| Participant {
+                                    party_id: target_party.id,
+                                    member_id: member.id,
+                                })
+                                .collect();
+
+                            let action = AppliedAction::Area(AreaAction {
+                                action,
+                                source,
+                                targets,
+                                cost,
+                            });
+
+                            // Neighboring targets may share the same group, e.g. when
+                            // hitting everyone, so each group is emitted only once.
+                            if self.last_area.as_ref() == Some(&action) {
+                                continue;
+                            }
+
+                            self.last_area = Some(action.clone());
+                            action
+                        }
                     };
 
-                    return Some(AppliedAction::Targeted(TargetedAction {
-                        action,
-                        source,
-                        target,
-                    }));
+                    if self.preferred.as_ref() == Some(&action) {
+                        continue;
+                    }
+
+                    return Some(action);
                 }
             }
         }
     }
 }
 
+/// Locates a member by its index in the concatenated member lists of several parties.
+///
+/// ## Returns
+/// The party of the member and the index of the member in the party.
+fn locate<'a>(
+    parties: impl IntoIterator<Item = &'a Party>,
+    mut index: usize,
+) -> (&'a Party, usize) {
+    for party in parties {
+        if index < party.len() {
+            return (party, index);
+        }
+
+        index -= party.len();
+    }
+
+    unreachable!("Target indices are within the member lists")
+}
+
 impl ActionTargetIterator {
-    pub fn new(member: PartyMember, enemies: Range<usize>) -> Self {
+    pub fn new(member: PartyMember, enemies: Range<usize>, allies: Range<usize>) -> Self {
         let actions = member.actions();
-        let enemy_index = enemies.start;
         Self {
             iter: Some(actions),
+            target_index: enemies.end,
+            targets: enemies.clone(),
             enemies,
-            enemy_index,
+            allies,
             action: None,
         }
     }
 }
 
 impl Iterator for ActionTargetIterator {
-    type Item = (Action, usize);
+    type Item = (Ability, usize);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(actions) = &mut self.iter {
-            // Reset to the first enemy if needed and require a new action.
-            if self.enemy_index >= self.enemies.end {
-                self.enemy_index = self.enemies.start;
+        while let Some(actions) = &mut self.iter {
+            // Once all targets were visited, require a new action.
+            if self.target_index >= self.targets.end {
                 self.action = None;
             }
 
-            // Generate a new action if needed.
+            // Generate a new action if needed and select its targets.
             if self.action.is_none() {
                 self.action = actions.next();
+                if let Some(ability) = self.action.as_ref() {
+                    self.targets = if ability.action.targets_allies() {
+                        self.allies.clone()
+                    } else {
+                        self.enemies.clone()
+                    };
+                    self.target_index = self.targets.start;
+                    continue;
+                }
             }
 
-            // If there is an action, apply it to the current enemy.
+            // If there is an action, apply it to the current target.
             if let Some(action) = self.action.as_ref() {
-                let index = self.enemy_index;
-                self.enemy_index += 1;
+                let index = self.target_index;
+                self.target_index += 1;
                 return Some((action.clone(), index));
             }
 
@@ -185,29 +403,38 @@ impl Iterator for ActionTargetIterator {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::action::SimpleAttackAction;
-    use crate::weapon::{Fists, Stick, Weapon};
+    use crate::action::{Action, AttackRoll, Cost, DefendAction, HealAction, SimpleAttackAction};
+    use crate::party_member::Row;
+    use crate::weapon::{Weapon, WeaponRange};
 
     #[test]
     fn action_target_iterator_works() {
         let mut iter = ActionTargetIterator::new(
-            PartyMember {
-                id: 0,
-                health: 25.0,
-                damage_taken: 0.0,
-                weapon: Weapon::Stick(Stick { damage: 10.0 }),
-                can_act: true,
-            },
+            PartyMember::new(
+                0,
+                25.0,
+                Weapon {
+                    name: "a stick".to_string(),
+                    damage: 10.0,
+                    ..Default::default()
+                },
+            ),
             0..10,
+            0..1,
         );
 
         for t in 0..10 {
             assert_eq!(
-                iter.next(),
+                iter.next().map(|(ability, t)| (ability.action, t)),
                 Some((
                     Action::SimpleAttack(SimpleAttackAction {
-                        weapon: Some(Weapon::Stick(Stick { damage: 10.0 })),
-                        damage: 10.0
+                        weapon: Some(Weapon {
+                            name: "a stick".to_string(),
+                            damage: 10.0,
+                            ..Default::default()
+                        }),
+                        damage: 10.0,
+                        roll: AttackRoll::Hit,
                     }),
                     t
                 ))
@@ -216,11 +443,12 @@ mod tests {
 
         for t in 0..10 {
             assert_eq!(
-                iter.next(),
+                iter.next().map(|(ability, t)| (ability.action, t)),
                 Some((
                     Action::SimpleAttack(SimpleAttackAction {
                         weapon: None,
-                        damage: 1.0
+                        damage: 1.0,
+                        roll: AttackRoll::Hit,
                     }),
                     t
                 ))
@@ -235,23 +463,31 @@ mod tests {
     #[test]
     fn action_target_iterator_sliced() {
         let mut iter = ActionTargetIterator::new(
-            PartyMember {
-                id: 0,
-                health: 25.0,
-                damage_taken: 0.0,
-                weapon: Weapon::Stick(Stick { damage: 10.0 }),
-                can_act: true,
-            },
+            PartyMember::new(
+                0,
+                25.0,
+                Weapon {
+                    name: "a stick".to_string(),
+                    damage: 10.0,
+                    ..Default::default()
+                },
+            ),
             10..20,
+            0..1,
         );
 
         for t in 10..20 {
             assert_eq!(
-                iter.next(),
+                iter.next().map(|(ability, t)| (ability.action, t)),
                 Some((
                     Action::SimpleAttack(SimpleAttackAction {
-                        weapon: Some(Weapon::Stick(Stick { damage: 10.0 })),
-                        damage: 10.0
+                        weapon: Some(Weapon {
+                            name: "a stick".to_string(),
+                            damage: 10.0,
+                            ..Default::default()
+                        }),
+                        damage: 10.0,
+                        roll: AttackRoll::Hit,
                     }),
                     t
                 ))
@@ -260,11 +496,12 @@ mod tests {
 
         for t in 10..20 {
             assert_eq!(
-                iter.next(),
+                iter.next().map(|(ability, t)| (ability.action, t)),
                 Some((
                     Action::SimpleAttack(SimpleAttackAction {
                         weapon: None,
-                        damage: 1.0
+                        damage: 1.0,
+                        roll: AttackRoll::Hit,
                     }),
                     t
                 ))
@@ -279,56 +516,75 @@ mod tests {
         let heroes = Party {
             id: 0,
             members: vec![
-                PartyMember {
-                    id: 0,
-                    health: 25.0,
-                    damage_taken: 0.0,
-                    weapon: Weapon::Stick(Stick { damage: 10.0 }),
-                    can_act: true,
-                },
-                PartyMember {
-                    id: 1,
-                    health: 25.0,
-                    damage_taken: 0.0,
-                    weapon: Weapon::Fists(Fists { damage: 5.0 }),
-                    can_act: true,
-                },
+                PartyMember::new(
+                    0,
+                    25.0,
+                    Weapon {
+                        name: "a stick".to_string(),
+                        damage: 10.0,
+                        ..Default::default()
+                    },
+                ),
+                PartyMember::new(
+                    1,
+                    25.0,
+                    Weapon {
+                        name: "their fists".to_string(),
+                        damage: 5.0,
+                        ..Default::default()
+                    },
+                ),
             ],
             can_retreat: false,
             retreated: false,
+            alliance: 0,
+            escape_chance: None,
+            personality: None,
         };
 
         let villains = Party {
             id: 1,
             members: vec![
-                PartyMember {
-                    id: 0,
-                    health: 25.0,
-                    damage_taken: 0.0,
-                    weapon: Weapon::Stick(Stick { damage: 10.0 }),
-                    can_act: true,
-                },
-                PartyMember {
-                    id: 1,
-                    health: 25.0,
-                    damage_taken: 0.0,
-                    weapon: Weapon::Stick(Stick { damage: 10.0 }),
-                    can_act: true,
-                },
+                PartyMember::new(
+                    0,
+                    25.0,
+                    Weapon {
+                        name: "a stick".to_string(),
+                        damage: 10.0,
+                        ..Default::default()
+                    },
+                ),
+                PartyMember::new(
+                    1,
+                    25.0,
+                    Weapon {
+                        name: "a stick".to_string(),
+                        damage: 10.0,
+                        ..Default::default()
+                    },
+                ),
             ],
             can_retreat: false,
             retreated: false,
+            alliance: 1,
+            escape_chance: None,
+            personality: None,
         };
 
-        let mut iter = ActionIterator::new(heroes, villains);
+        let mut iter = ActionIterator::new(heroes, Vec::default(), vec![villains]);
 
         // First player attacks first opponent.
         assert_eq!(
             iter.next(),
             Some(AppliedAction::Targeted(TargetedAction {
                 action: Action::SimpleAttack(SimpleAttackAction {
-                    weapon: Some(Weapon::Stick(Stick { damage: 10.0 })),
-                    damage: 10.0
+                    weapon: Some(Weapon {
+                        name: "a stick".to_string(),
+                        damage: 10.0,
+                        ..Default::default()
+                    }),
+                    damage: 10.0,
+                    roll: AttackRoll::Hit,
                 }),
                 source: Participant {
                     party_id: 0,
@@ -337,7 +593,8 @@ mod tests {
                 target: Participant {
                     party_id: 1,
                     member_id: 0
-                }
+                },
+                cost: Cost::default(),
             }))
         );
 
@@ -346,8 +603,13 @@ mod tests {
             iter.next(),
             Some(AppliedAction::Targeted(TargetedAction {
                 action: Action::SimpleAttack(SimpleAttackAction {
-                    weapon: Some(Weapon::Stick(Stick { damage: 10.0 })),
-                    damage: 10.0
+                    weapon: Some(Weapon {
+                        name: "a stick".to_string(),
+                        damage: 10.0,
+                        ..Default::default()
+                    }),
+                    damage: 10.0,
+                    roll: AttackRoll::Hit,
                 }),
                 source: Participant {
                     party_id: 0,
@@ -356,7 +618,8 @@ mod tests {
                 target: Participant {
                     party_id: 1,
                     member_id: 1
-                }
+                },
+                cost: Cost::default(),
             }))
         );
 
@@ -366,7 +629,8 @@ mod tests {
             Some(AppliedAction::Targeted(TargetedAction {
                 action: Action::SimpleAttack(SimpleAttackAction {
                     weapon: None,
-                    damage: 1.0
+                    damage: 1.0,
+                    roll: AttackRoll::Hit,
                 }),
                 source: Participant {
                     party_id: 0,
@@ -375,7 +639,8 @@ mod tests {
                 target: Participant {
                     party_id: 1,
                     member_id: 0
-                }
+                },
+                cost: Cost::default(),
             }))
         );
 
@@ -385,7 +650,8 @@ mod tests {
             Some(AppliedAction::Targeted(TargetedAction {
                 action: Action::SimpleAttack(SimpleAttackAction {
                     weapon: None,
-                    damage: 1.0
+                    damage: 1.0,
+                    roll: AttackRoll::Hit,
                 }),
                 source: Participant {
                     party_id: 0,
@@ -394,7 +660,8 @@ mod tests {
                 target: Participant {
                     party_id: 1,
                     member_id: 1
-                }
+                },
+                cost: Cost::default(),
             }))
         );
 
@@ -403,8 +670,13 @@ mod tests {
             iter.next(),
             Some(AppliedAction::Targeted(TargetedAction {
                 action: Action::SimpleAttack(SimpleAttackAction {
-                    weapon: Some(Weapon::Fists(Fists { damage: 5.0 })),
-                    damage: 5.0
+                    weapon: Some(Weapon {
+                        name: "their fists".to_string(),
+                        damage: 5.0,
+                        ..Default::default()
+                    }),
+                    damage: 5.0,
+                    roll: AttackRoll::Hit,
                 }),
                 source: Participant {
                     party_id: 0,
@@ -413,7 +685,8 @@ mod tests {
                 target: Participant {
                     party_id: 1,
                     member_id: 0
-                }
+                },
+                cost: Cost::default(),
             }))
         );
 
@@ -422,8 +695,13 @@ mod tests {
             iter.next(),
             Some(AppliedAction::Targeted(TargetedAction {
                 action: Action::SimpleAttack(SimpleAttackAction {
-                    weapon: Some(Weapon::Fists(Fists { damage: 5.0 })),
-                    damage: 5.0
+                    weapon: Some(Weapon {
+                        name: "their fists".to_string(),
+                        damage: 5.0,
+                        ..Default::default()
+                    }),
+                    damage: 5.0,
+                    roll: AttackRoll::Hit,
                 }),
                 source: Participant {
                     party_id: 0,
@@ -432,7 +710,8 @@ mod tests {
                 target: Participant {
                     party_id: 1,
                     member_id: 1
-                }
+                },
+                cost: Cost::default(),
             }))
         );
 
@@ -442,7 +721,8 @@ mod tests {
             Some(AppliedAction::Targeted(TargetedAction {
                 action: Action::SimpleAttack(SimpleAttackAction {
                     weapon: None,
-                    damage: 1.0
+                    damage: 1.0,
+                    roll: AttackRoll::Hit,
                 }),
                 source: Participant {
                     party_id: 0,
@@ -451,7 +731,8 @@ mod tests {
                 target: Participant {
                     party_id: 1,
                     member_id: 0
-                }
+                },
+                cost: Cost::default(),
             }))
         );
 
@@ -461,7 +742,8 @@ mod tests {
             Some(AppliedAction::Targeted(TargetedAction {
                 action: Action::SimpleAttack(SimpleAttackAction {
                     weapon: None,
-                    damage: 1.0
+                    damage: 1.0,
+                    roll: AttackRoll::Hit,
                 }),
                 source: Participant {
                     party_id: 0,
@@ -470,10 +752,278 @@ mod tests {
                 target: Participant {
                     party_id: 1,
                     member_id: 1
-                }
+                },
+                cost: Cost::default(),
             }))
         );
 
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn preferred_action_is_emitted_first_and_once() {
+        let member = PartyMember::new(
+            0,
+            25.0,
+            Weapon {
+                name: "their fists".to_string(),
+                damage: 5.0,
+                ..Default::default()
+            },
+        );
+
+        let heroes = Party {
+            id: 0,
+            members: vec![member.clone()],
+            can_retreat: true,
+            retreated: false,
+            alliance: 0,
+            escape_chance: None,
+            personality: None,
+        };
+
+        let villains = Party {
+            id: 1,
+            members: vec![member],
+            can_retreat: false,
+            retreated: false,
+            alliance: 1,
+            escape_chance: None,
+            personality: None,
+        };
+
+        let all: Vec<_> =
+            ActionIterator::new(heroes.clone(), Vec::default(), vec![villains.clone()]).collect();
+        let preferred = all[1].clone();
+
+        let ordered: Vec<_> = ActionIterator::new(heroes, Vec::default(), vec![villains])
+            .with_preferred(Some(preferred.clone()))
+            .collect();
+
+        assert_eq!(ordered.len(), all.len());
+        assert_eq!(ordered[0], preferred);
+        assert_eq!(ordered.iter().filter(|&a| a == &preferred).count(), 1);
+    }
+
+    #[test]
+    fn heals_target_injured_allies() {
+        let member = PartyMember::new(
+            0,
+            25.0,
+            Weapon {
+                name: "their fists".to_string(),
+                damage: 5.0,
+                ..Default::default()
+            },
+        );
+
+        let heal = Action::Heal(HealAction { amount: 5.0 });
+        let healer = PartyMember {
+            abilities: vec![heal.clone().into()],
+            ..member.clone()
+        };
+        let injured = PartyMember {
+            id: 1,
+            health: 10.0,
+            ..member.clone()
+        };
+
+        let heroes = Party {
+            id: 0,
+            members: vec![healer, injured],
+            can_retreat: false,
+            retreated: false,
+            alliance: 0,
+            escape_chance: None,
+            personality: None,
+        };
+
+        let villains = Party {
+            id: 1,
+            members: vec![member],
+            can_retreat: false,
+            retreated: false,
+            alliance: 1,
+            escape_chance: None,
+            personality: None,
+        };
+
+        // Only the injured member can be healed; the healer is at full health.
+        let heals: Vec<_> = ActionIterator::new(heroes, Vec::default(), vec![villains])
+            .filter_map(|action| match action {
+                AppliedAction::Targeted(targeted) if targeted.action == heal => Some(targeted),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(heals.len(), 1);
+        assert_eq!(
+            heals[0].target,
+            Participant {
+                party_id: 0,
+                member_id: 1
+            }
+        );
+    }
+
+    #[test]
+    fn members_defend_themselves_and_guard_others() {
+        let member = |id| PartyMember {
+            abilities: vec![
+                Action::Defend(DefendAction {
+                    damage_multiplier: 0.5,
+                })
+                .into(),
+                Action::Guard.into(),
+            ],
+            ..PartyMember::new(id, 25.0, Weapon::default())
+        };
+
+        let party = |id| Party {
+            id,
+            members: vec![member(0), member(1)],
+            can_retreat: false,
+            retreated: false,
+            alliance: id,
+            escape_chance: None,
+            personality: None,
+        };
+
+        let stances: Vec<_> =
+            ActionIterator::new_in(party(0), vec![party(2)], vec![party(1)], 0..1)
+                .filter(|action| match action {
+                    AppliedAction::Targeted(targeted) => targeted.action.targets_allies(),
+                    _ => false,
+                })
+                .map(|action| action.to_string())
+                .collect();
+        assert_eq!(
+            stances,
+            vec![
+                "0:0 defends",
+                "0:0 guards 0:1",
+                "0:0 guards 2:0",
+                "0:0 guards 2:1"
+            ]
+        );
+    }
+
+    #[test]
+    fn area_actions_target_groups() {
+        let member = |id, weapon: Weapon| PartyMember::new(id, 25.0, weapon);
+
+        let mut villains = Party {
+            id: 1,
+            members: (0..4).map(|id| member(id, Weapon::default())).collect(),
+            can_retreat: false,
+            retreated: false,
+            alliance: 1,
+            escape_chance: None,
+            personality: None,
+        };
+        villains.members[3].health = 0.0;
+
+        let groups = |area| {
+            let weapon = Weapon {
+                damage: 5.0,
+                area,
+                ..Default::default()
+            };
+            let heroes = Party {
+                id: 0,
+                members: vec![member(0, weapon)],
+                can_retreat: false,
+                retreated: false,
+                alliance: 0,
+                escape_chance: None,
+                personality: None,
+            };
+
+            ActionIterator::new(heroes, Vec::default(), vec![villains.clone()])
+                .filter_map(|action| match action {
+                    AppliedAction::Area(area) => Some(
+                        area.targets
+                            .iter()
+                            .map(|target| target.member_id)
+                            .collect::<Vec<_>>(),
+                    ),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Cleaving hits the living neighbors of every target, each group only once.
+        assert_eq!(
+            groups(WeaponArea::Cleave),
+            vec![vec![0, 1], vec![0, 1, 2], vec![1, 2]]
+        );
+        assert_eq!(groups(WeaponArea::All), vec![vec![0, 1, 2]]);
+        assert!(groups(WeaponArea::Single).is_empty());
+    }
+
+    #[test]
+    fn melee_attacks_only_reach_the_front_row() {
+        let member = |id, weapon: Weapon, row| PartyMember {
+            row,
+            ..PartyMember::new(id, 25.0, weapon)
+        };
+
+        let mut villains = Party {
+            id: 1,
+            members: vec![
+                member(0, Weapon::default(), Row::Front),
+                member(1, Weapon::default(), Row::Back),
+            ],
+            can_retreat: false,
+            retreated: false,
+            alliance: 1,
+            escape_chance: None,
+            personality: None,
+        };
+
+        let actions = |range, villains: &Party| {
+            let weapon = Weapon {
+                damage: 5.0,
+                range,
+                ..Default::default()
+            };
+            let heroes = Party {
+                id: 0,
+                members: vec![
+                    member(0, weapon, Row::Front),
+                    member(1, Weapon::default(), Row::Back),
+                ],
+                can_retreat: false,
+                retreated: false,
+                alliance: 0,
+                escape_chance: None,
+                personality: None,
+            };
+
+            ActionIterator::new_in(heroes, Vec::default(), vec![villains.clone()], 0..1)
+                .with_formations(true)
+                .filter(|action| match action {
+                    AppliedAction::Targeted(targeted) => targeted.action.max_damage() == 5.0,
+                    _ => true,
+                })
+                .map(|action| action.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        // Every member may change rows after trying all of its other actions.
+        assert_eq!(
+            actions(WeaponRange::Melee, &villains),
+            vec!["0:0 attacks 1:0", "0:0 changes position"]
+        );
+        assert_eq!(
+            actions(WeaponRange::Ranged, &villains),
+            vec!["0:0 attacks 1:0", "0:0 attacks 1:1", "0:0 changes position"]
+        );
+
+        // Once the front row has fallen, the back row is within reach.
+        villains.members[0].health = 0.0;
+        assert_eq!(
+            actions(WeaponRange::Melee, &villains),
+            vec!["0:0 attacks 1:1", "0:0 changes position"]
+        );
+    }
 }
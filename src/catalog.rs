@@ -0,0 +1,127 @@
+use crate::scenario::{
+    parse_json, parse_toml, read_file, FileFormat, ScenarioError, WeaponDefinition,
+};
+use crate::weapon::Weapon;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The weapons available without loading a catalog.
+const BUILTIN_CATALOG: &str = include_str!("../catalog/weapons.toml");
+
+/// A catalog of weapons, identified by their ID.
+#[derive(Debug, Clone, Default)]
+pub struct WeaponCatalog {
+    /// The weapons by their ID.
+    weapons: BTreeMap<String, Weapon>,
+}
+
+/// The file representation of a catalog.
+type CatalogDefinition = BTreeMap<String, WeaponDefinition>;
+
+impl WeaponCatalog {
+    /// Gets the built-in catalog, see `catalog/weapons.toml`.
+    pub fn builtin() -> Self {
+        Self::from_toml(BUILTIN_CATALOG).expect("the built-in catalog is valid")
+    }
+
+    /// Loads a catalog from a file. The format is determined by the
+    /// file extension, which must be either `.toml` or `.json`.
+    pub fn load(path: &Path) -> Result<Self, ScenarioError> {
+        match read_file(path)? {
+            (text, FileFormat::Toml) => Self::from_toml(&text),
+            (text, FileFormat::Json) => Self::from_json(&text),
+        }
+    }
+
+    /// Parses a catalog from TOML.
+    pub fn from_toml(text: &str) -> Result<Self, ScenarioError> {
+        Self::build(parse_toml(text)?)
+    }
+
+    /// Parses a catalog from JSON.
+    pub fn from_json(text: &str) -> Result<Self, ScenarioError> {
+        Self::build(parse_json(text)?)
+    }
+
+    /// Validates the definition and builds the catalog from it.
+    /// Weapons are named after their ID unless a name is given.
+    fn build(definition: CatalogDefinition) -> Result<Self, ScenarioError> {
+        let mut weapons = BTreeMap::default();
+        for (id, weapon) in definition {
+            let base = Weapon {
+                name: id.clone(),
+                ..Default::default()
+            };
+            weapons.insert(id.clone(), weapon.apply(base, &id)?);
+        }
+
+        Ok(Self { weapons })
+    }
+
+    /// Gets a weapon by its ID.
+    pub fn get(&self, id: &str) -> Option<&Weapon> {
+        self.weapons.get(id)
+    }
+
+    /// Adds all weapons of another catalog, replacing weapons of the same ID.
+    pub fn extend(&mut self, other: WeaponCatalog) {
+        self.weapons.extend(other.weapons);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::status_effect::{StatusEffect, StatusEffectKind};
+    use crate::weapon::{DamageType, Dice, WeaponRange};
+
+    #[test]
+    fn builtin_catalog_works() {
+        let catalog = WeaponCatalog::builtin();
+        assert_eq!(
+            catalog.get("stick"),
+            Some(&Weapon {
+                name: "a stick".to_string(),
+                damage: 2.0,
+                ..Default::default()
+            })
+        );
+
+        let fangs = catalog.get("venomous_fangs").unwrap();
+        assert_eq!(fangs.damage_type, DamageType::Piercing);
+        assert_eq!(
+            fangs.effects,
+            vec![StatusEffect {
+                kind: StatusEffectKind::Poison { damage: 2.0 },
+                duration: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn catalogs_extend_each_other() {
+        let mut catalog = WeaponCatalog::builtin();
+        catalog.extend(
+            WeaponCatalog::from_json(
+                r#"{
+                    "stick": { "damage": 3 },
+                    "crossbow": { "name": "a crossbow", "damage": 4, "dice": "1d8", "range": "ranged" }
+                }"#,
+            )
+            .unwrap(),
+        );
+
+        // Redefined weapons are replaced entirely.
+        assert_eq!(catalog.get("stick").unwrap().name, "stick");
+        assert_eq!(catalog.get("stick").unwrap().damage, 3.0);
+
+        let crossbow = catalog.get("crossbow").unwrap();
+        assert_eq!(crossbow.dice, Dice { count: 1, sides: 8 });
+        assert_eq!(crossbow.range, WeaponRange::Ranged);
+
+        match WeaponCatalog::from_toml("[sword]\ntype = \"stick\"") {
+            Err(ScenarioError::Invalid { field, .. }) => assert_eq!(field, "sword.type"),
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+}
@@ -1,24 +1,74 @@
+use crate::action::{Action, AppliedAction, AreaAction, Cost, EscapeAction, TargetedAction};
+use crate::action_iterator::ActionIterator;
 use crate::party::{Participant, Party};
 use crate::party_member::PartyMember;
+use serde::Serialize;
 
 /// A conflict, specifically the state of conflict at a given turn.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
 pub struct Conflict {
-    /// The party initiating the conflict, the maximizing player.
-    pub initiator: Party,
-    /// The other involved party, the minimizing player.
-    pub opponent: Party,
+    /// All involved parties. The first party initiated the conflict and is the maximizing
+    /// player; every party outside its alliance is assumed to play against it, i.e. is
+    /// a minimizing player, even if the other parties are hostile to each other as well.
+    pub parties: Vec<Party>,
+    /// The order in which party members take their turns.
+    pub turn_order: TurnOrder,
+    /// Whether members may change rows of their party's formation. Melee attacks
+    /// only reach the front row either way, see [`Party::is_within_reach`].
+    pub formations: bool,
+}
+
+/// The order in which party members take their turns.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TurnOrder {
+    /// The parties take turns after every single action in the order they are listed,
+    /// starting with the initiator. Any member of the party whose turn it is may act.
+    /// Parties that were defeated or retreated are skipped.
+    Alternating {
+        /// The index of the party acting next.
+        party: usize,
+    },
+    /// Every member acts once per round, fastest first. On equal speed,
+    /// members of parties listed first act first, then members by their ID.
+    Initiative {
+        /// The position in the initiative queue of the next member to act.
+        position: usize,
+    },
 }
 
 impl Conflict {
+    /// Gets the party initiating the conflict.
+    pub fn initiator(&self) -> &Party {
+        &self.parties[0]
+    }
+
+    /// Iterates over all parties outside the alliance of the initiating party.
+    pub fn hostile_parties(&self) -> impl Iterator<Item = &Party> {
+        self.parties_hostile_to(self.initiator())
+    }
+
+    /// Iterates over all parties outside the alliance of the given party.
+    pub fn parties_hostile_to<'a>(&'a self, party: &'a Party) -> impl Iterator<Item = &'a Party> {
+        self.parties
+            .iter()
+            .filter(move |other| !other.is_allied_with(party))
+    }
+
     /// Selects the action target by ID.
     pub fn action_target(&self, party_id: usize) -> &Party {
-        if self.initiator.id == party_id {
-            &self.initiator
-        } else {
-            debug_assert_eq!(self.opponent.id, party_id);
-            &self.opponent
-        }
+        self.parties
+            .iter()
+            .find(|party| party.id == party_id)
+            .expect("The party is involved in the conflict")
+    }
+
+    /// Selects the action target by ID.
+    pub fn action_target_mut(&mut self, party_id: usize) -> &mut Party {
+        self.parties
+            .iter_mut()
+            .find(|party| party.id == party_id)
+            .expect("The party is involved in the conflict")
     }
 
     /// Selects the action target by ID.
@@ -26,4 +76,655 @@ impl Conflict {
         let party = self.action_target(target.party_id);
         &party.members[target.member_id]
     }
+
+    /// Finds the member intercepting attacks on the participant, if anyone guards them.
+    pub fn guard_of(&self, ally: &Participant) -> Option<Participant> {
+        let allies = self.action_target(ally.party_id);
+        self.parties
+            .iter()
+            .filter(|party| party.is_allied_with(allies))
+            .find_map(|party| {
+                let guard = party.members.iter().find(|m| m.is_guarding(ally))?;
+                Some(Participant {
+                    party_id: party.id,
+                    member_id: guard.id,
+                })
+            })
+    }
+
+    /// Returns all participants in initiative order, i.e. sorted by descending speed.
+    pub fn initiative_queue(&self) -> Vec<Participant> {
+        let mut queue: Vec<_> = self
+            .parties
+            .iter()
+            .enumerate()
+            .flat_map(|(order, party)| {
+                party.members.iter().map(move |member| {
+                    let participant = Participant {
+                        party_id: party.id,
+                        member_id: member.id,
+                    };
+                    (member.speed, order, participant)
+                })
+            })
+            .collect();
+
+        queue.sort_by(|(a_speed, a_order, a), (b_speed, b_order, b)| {
+            b_speed
+                .cmp(a_speed)
+                .then(a_order.cmp(b_order))
+                .then(a.member_id.cmp(&b.member_id))
+        });
+
+        queue
+            .into_iter()
+            .map(|(_, _, participant)| participant)
+            .collect()
+    }
+
+    /// Determines the member acting next when turns follow the initiative order.
+    /// Members that are dead or fled are skipped; members that are merely unable
+    /// to act, e.g. because they are stunned, still take their turn.
+    ///
+    /// ## Returns
+    /// The position of the member in the initiative queue and the member itself,
+    /// or [`None`] if turns alternate between parties or no member is able to act.
+    pub fn next_actor(&self) -> Option<(usize, Participant)> {
+        let position = match self.turn_order {
+            TurnOrder::Alternating { .. } => return None,
+            TurnOrder::Initiative { position } => position,
+        };
+
+        // Starting at the current position, wrap around into the next round.
+        let queue = self.initiative_queue();
+        (position..queue.len())
+            .chain(0..position)
+            .map(|index| (index, queue[index].clone()))
+            .find(|(_, participant)| self.targeted_member(participant).takes_turns())
+    }
+
+    /// Determines the party acting next, i.e. the party whose turn it is or the party
+    /// of the next member in initiative order. If no member is able to act at all,
+    /// this is the initiating party.
+    pub fn acting_party(&self) -> &Party {
+        match (self.next_actor(), self.turn_order) {
+            (Some((_, actor)), _) => self.action_target(actor.party_id),
+            (None, TurnOrder::Alternating { party }) => &self.parties[party],
+            (None, TurnOrder::Initiative { .. }) => self.initiator(),
+        }
+    }
+
+    /// Determines whether the initiating party or one of its allies acts next.
+    pub fn is_initiator_turn(&self) -> bool {
+        self.acting_party().is_allied_with(self.initiator())
+    }
+
+    /// Creates an iterator over the actions available to the party acting next.
+    /// Either any member of the party may act, or only the next one in initiative order.
+    /// Members target all parties outside their alliance, or their allies for supporting actions.
+    ///
+    /// ## Returns
+    /// The iterator, or [`None`] if no member of the party is able to act.
+    pub fn actions(&self) -> Option<ActionIterator> {
+        let current = self.acting_party();
+
+        // A party that retreated without deciding the conflict has left the fight
+        // between the others; only the party granted the last action may chase it.
+        let is_decided = self.is_decided();
+        let (allies, opponents): (Vec<_>, Vec<_>) = self
+            .parties
+            .iter()
+            .filter(|party| party.id != current.id)
+            .filter(|party| is_decided || !party.has_retreated())
+            .cloned()
+            .partition(|party| party.is_allied_with(current));
+
+        match (self.next_actor(), self.turn_order) {
+            (Some((_, actor)), _) => {
+                let index = current
+                    .members
+                    .iter()
+                    .position(|m| m.id == actor.member_id)?;
+                let iter =
+                    ActionIterator::new_in(current.clone(), allies, opponents, index..index + 1);
+                Some(iter.with_formations(self.formations))
+            }
+            (None, TurnOrder::Alternating { .. }) => {
+                let iter = ActionIterator::new(current.clone(), allies, opponents);
+                Some(iter.with_formations(self.formations))
+            }
+            (None, TurnOrder::Initiative { .. }) => None,
+        }
+    }
+
+    /// Determines whether the outcome of the conflict is decided, i.e. either the
+    /// initiating party or all parties hostile to it were defeated or retreated.
+    pub fn is_decided(&self) -> bool {
+        !self.initiator().is_engaged() || !self.hostile_parties().any(Party::is_engaged)
+    }
+
+    /// Determines whether the conflict ended with the action that led to this state:
+    /// a party whose retreat decided the encounter grants the next party one last action.
+    ///
+    /// ## Arguments
+    /// * `action` - The action that led to this state, if any.
+    pub fn has_ended_after(&self, action: Option<&AppliedAction>) -> bool {
+        self.is_decided() && action != Some(&AppliedAction::Flee)
+    }
+
+    /// Lets the source of an action pay for it.
+    fn spend(&mut self, source: &Participant, cost: &Cost) {
+        if !cost.is_free() {
+            let party = self.action_target_mut(source.party_id);
+            party.members[source.member_id].spend(cost);
+        }
+    }
+
+    /// Applies an action taken by a party and produces the resulting state.
+    ///
+    /// ## Arguments
+    /// * `party_id` - The ID of the party taking the action.
+    /// * `action` - The action to apply.
+    ///
+    /// ## Returns
+    /// The state after the action took place, or [`None`] if the action could not be applied.
+    pub fn apply(&self, party_id: usize, action: &AppliedAction) -> Option<Conflict> {
+        let mut state = self.clone();
+        let actor = self.next_actor();
+        if let Some((position, actor)) = &actor {
+            debug_assert_eq!(actor.party_id, party_id);
+            let position = (position + 1) % self.initiative_queue().len();
+            state.turn_order = TurnOrder::Initiative { position };
+        }
+
+        // Stances last until the member who took them acts again. When parties alternate,
+        // members keep their stance while others of their party act.
+        let member = match (&actor, action) {
+            (Some((_, actor)), _) => Some(actor),
+            (None, AppliedAction::Targeted(TargetedAction { source, .. }))
+            | (None, AppliedAction::Area(AreaAction { source, .. }))
+            | (None, AppliedAction::Move(source))
+            | (None, AppliedAction::Escape(EscapeAction { member: source, .. })) => Some(source),
+            (None, AppliedAction::Flee | AppliedAction::Pass) => None,
+        };
+        if let Some(member) = member {
+            let party = state.action_target_mut(member.party_id);
+            party.members[member.member_id].stance = None;
+        }
+
+        match action {
+            AppliedAction::Flee => {
+                state.action_target_mut(party_id).retreat();
+            }
+            AppliedAction::Pass => {}
+            AppliedAction::Escape(escape) => {
+                debug_assert_eq!(escape.member.party_id, party_id);
+                if escape.escaped {
+                    state
+                        .action_target_mut(party_id)
+                        .escape(escape.member.member_id);
+                }
+            }
+            AppliedAction::Move(member) => {
+                debug_assert_eq!(member.party_id, party_id);
+                let member = &mut state.action_target_mut(party_id).members[member.member_id];
+                member.row = member.row.other();
+            }
+            AppliedAction::Targeted(action) => {
+                debug_assert_eq!(action.source.party_id, party_id);
+
+                // Guards intercept attacks on the allies they protect.
+                let target = match state.guard_of(&action.target) {
+                    Some(guard) if !action.action.targets_allies() => guard,
+                    _ => action.target.clone(),
+                };
+
+                let party = state.action_target_mut(target.party_id);
+                let mut member = party.members[target.member_id].clone();
+                if !member.handle_action(&action.action) {
+                    return None;
+                }
+
+                party.replace_member(member);
+                if action.action.effect() == &Action::Guard {
+                    let party = state.action_target_mut(action.source.party_id);
+                    party.members[action.source.member_id].guard(action.target.clone());
+                }
+
+                state.spend(&action.source, &action.cost);
+            }
+            AppliedAction::Area(action) => {
+                debug_assert_eq!(action.source.party_id, party_id);
+                for participant in &action.targets {
+                    let party = state.action_target_mut(participant.party_id);
+                    let mut target = party.members[participant.member_id].clone();
+                    if !target.handle_action(&action.action) {
+                        return None;
+                    }
+
+                    party.replace_member(target);
+                }
+
+                state.spend(&action.source, &action.cost);
+            }
+        }
+
+        // Status effects of the acting members tick at the end of their turn.
+        match actor {
+            Some((_, actor)) => {
+                let party = state.action_target_mut(actor.party_id);
+                party.members[actor.member_id].tick_effects();
+            }
+            None => state.action_target_mut(party_id).tick_effects(),
+        }
+
+        // Turns pass to the next party still engaged in the conflict.
+        if let TurnOrder::Alternating { party } = state.turn_order {
+            let count = state.parties.len();
+            let party = (1..=count)
+                .map(|offset| (party + offset) % count)
+                .find(|&index| state.parties[index].is_engaged())
+                .unwrap_or((party + 1) % count);
+            state.turn_order = TurnOrder::Alternating { party };
+        }
+
+        // Cooldowns start once the turn of the caster has ended.
+        let cast = match action {
+            AppliedAction::Targeted(TargetedAction {
+                action: Action::Spell(spell),
+                source,
+                ..
+            })
+            | AppliedAction::Area(AreaAction {
+                action: Action::Spell(spell),
+                source,
+                ..
+            }) => Some((source, spell)),
+            _ => None,
+        };
+        if let Some((source, spell)) = cast {
+            let party = state.action_target_mut(source.party_id);
+            party.members[source.member_id].start_cooldown(&spell.name);
+        }
+
+        Some(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::{AttackRoll, SimpleAttackAction};
+    use crate::party_member::Row;
+    use crate::spell::Spell;
+    use crate::status_effect::{StatusEffect, StatusEffectKind};
+    use crate::weapon::{Weapon, WeaponArea};
+
+    fn member(id: usize, speed: u32) -> PartyMember {
+        let weapon = Weapon {
+            name: "their fists".to_string(),
+            damage: 10.0,
+            ..Default::default()
+        };
+        PartyMember {
+            speed,
+            ..PartyMember::new(id, 10.0, weapon)
+        }
+    }
+
+    fn participant(party_id: usize, member_id: usize) -> Participant {
+        Participant {
+            party_id,
+            member_id,
+        }
+    }
+
+    #[test]
+    fn initiative_queue_is_ordered_by_speed() {
+        let conflict = Conflict {
+            parties: vec![
+                Party::new(0, vec![member(0, 1), member(1, 5)]),
+                Party::new(1, vec![member(0, 5), member(1, 9)]),
+            ],
+            turn_order: TurnOrder::Initiative { position: 0 },
+            formations: false,
+        };
+
+        assert_eq!(
+            conflict.initiative_queue(),
+            vec![
+                participant(1, 1),
+                participant(0, 1),
+                participant(1, 0),
+                participant(0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn next_actor_skips_members_unable_to_act() {
+        let mut conflict = Conflict {
+            parties: vec![
+                Party::new(0, vec![member(0, 1)]),
+                Party::new(1, vec![member(0, 5), member(1, 3)]),
+            ],
+            turn_order: TurnOrder::Initiative { position: 1 },
+            formations: false,
+        };
+        conflict.parties[1].members[1].health = 0.0;
+
+        // The dead member is skipped and the queue wraps into the next round.
+        assert_eq!(conflict.next_actor(), Some((2, participant(0, 0))));
+
+        conflict.turn_order = TurnOrder::Alternating { party: 0 };
+        assert_eq!(conflict.next_actor(), None);
+    }
+
+    #[test]
+    fn stunned_members_pass_their_turn() {
+        let mut conflict = Conflict {
+            parties: vec![
+                Party::new(0, vec![member(0, 5)]),
+                Party::new(1, vec![member(0, 1)]),
+            ],
+            turn_order: TurnOrder::Initiative { position: 0 },
+            formations: false,
+        };
+        conflict.parties[0].members[0].effects.push(StatusEffect {
+            kind: StatusEffectKind::Stun,
+            duration: 1,
+        });
+
+        // The stunned member still takes their turn, but can only pass.
+        assert_eq!(conflict.next_actor(), Some((0, participant(0, 0))));
+        let actions: Vec<_> = conflict.actions().unwrap().collect();
+        assert_eq!(actions, vec![AppliedAction::Pass]);
+
+        // The stun wears off at the end of the turn.
+        let state = conflict.apply(0, &AppliedAction::Pass).unwrap();
+        assert!(state.parties[0].members[0].effects.is_empty());
+        assert!(state.parties[0].members[0].can_act());
+        assert_eq!(state.next_actor(), Some((1, participant(1, 0))));
+    }
+
+    #[test]
+    fn area_actions_hit_every_target() {
+        let conflict = Conflict {
+            parties: vec![
+                Party::new(0, vec![member(0, 1)]),
+                Party::new(1, vec![member(0, 1), member(1, 1), member(2, 1)]),
+            ],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
+        };
+
+        let action = AppliedAction::Area(AreaAction {
+            action: Action::SimpleAttack(SimpleAttackAction {
+                weapon: Some(Weapon {
+                    name: "a fireball".to_string(),
+                    damage: 4.0,
+                    area: WeaponArea::All,
+                    ..Default::default()
+                }),
+                damage: 4.0,
+                roll: AttackRoll::Hit,
+            }),
+            source: participant(0, 0),
+            targets: vec![participant(1, 0), participant(1, 2)],
+            cost: Cost::default(),
+        });
+
+        let state = conflict.apply(0, &action).unwrap();
+        let health: Vec<_> = state.parties[1].members.iter().map(|m| m.health).collect();
+        assert_eq!(health, vec![6.0, 10.0, 6.0]);
+    }
+
+    #[test]
+    fn spells_go_on_cooldown() {
+        let mut conflict = Conflict {
+            parties: vec![
+                Party::new(0, vec![member(0, 1)]),
+                Party::new(1, vec![member(0, 1)]),
+            ],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
+        };
+        conflict.parties[1].members[0].health = 30.0;
+        conflict.parties[0].members[0].spells.push(Spell {
+            name: "Zap".to_string(),
+            action: Action::SimpleAttack(SimpleAttackAction {
+                weapon: Some(Weapon {
+                    name: "Zap".to_string(),
+                    damage: 3.0,
+                    ..Default::default()
+                }),
+                damage: 3.0,
+                roll: AttackRoll::Hit,
+            }),
+            cost: Cost::default(),
+            area: WeaponArea::Single,
+            cooldown: 1,
+            ready_in: 0,
+        });
+
+        let is_spell = |action: &AppliedAction| {
+            matches!(
+                action,
+                AppliedAction::Targeted(TargetedAction {
+                    action: Action::Spell(_),
+                    ..
+                })
+            )
+        };
+
+        let cast = conflict
+            .actions()
+            .unwrap()
+            .find(|action| is_spell(action))
+            .unwrap();
+        let state = conflict.apply(0, &cast).unwrap();
+        assert_eq!(state.parties[1].members[0].health, 27.0);
+
+        // The spell is unavailable for one turn of the caster.
+        let state = state.apply(1, &AppliedAction::Pass).unwrap();
+        assert!(!state.actions().unwrap().any(|action| is_spell(&action)));
+        let state = state.apply(0, &AppliedAction::Pass).unwrap();
+        let state = state.apply(1, &AppliedAction::Pass).unwrap();
+        assert!(state.actions().unwrap().any(|action| is_spell(&action)));
+    }
+
+    #[test]
+    fn alternating_turns_skip_defeated_parties() {
+        let mut conflict = Conflict {
+            parties: vec![
+                Party::new(0, vec![member(0, 1)]),
+                Party::new(1, vec![member(0, 1)]),
+                Party::new(2, vec![member(0, 1)]),
+            ],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
+        };
+
+        // Without alliances, everyone attacks everyone else. Each
+        // attack is tried against every target in turn.
+        let targets: Vec<_> = conflict
+            .actions()
+            .unwrap()
+            .take(2)
+            .flat_map(|action| action.targets().to_vec())
+            .collect();
+        assert_eq!(targets, vec![participant(1, 0), participant(2, 0)]);
+
+        conflict.parties[1].members[0].health = 0.0;
+        let state = conflict.apply(0, &AppliedAction::Pass).unwrap();
+        assert_eq!(state.turn_order, TurnOrder::Alternating { party: 2 });
+        assert_eq!(state.acting_party().id, 2);
+        assert!(!state.is_initiator_turn());
+        assert!(!state.is_decided());
+
+        let state = state.apply(2, &AppliedAction::Pass).unwrap();
+        assert_eq!(state.turn_order, TurnOrder::Alternating { party: 0 });
+    }
+
+    #[test]
+    fn allies_fight_on_the_same_side() {
+        let mut conflict = Conflict {
+            parties: vec![
+                Party::new(0, vec![member(0, 1)]),
+                Party::new(1, vec![member(0, 1)]),
+                Party::new(2, vec![member(0, 1)]),
+            ],
+            turn_order: TurnOrder::Alternating { party: 2 },
+            formations: false,
+        };
+        conflict.parties[2].alliance = 0;
+
+        // Allies take the side of the initiator and never attack each other.
+        assert!(conflict.is_initiator_turn());
+        assert!(conflict
+            .actions()
+            .unwrap()
+            .flat_map(|action| action.targets().to_vec())
+            .all(|target| target == participant(1, 0)));
+
+        // The conflict is decided once no hostile party remains.
+        conflict.parties[1].members[0].health = 0.0;
+        assert!(conflict.is_decided());
+        assert!(conflict.has_ended_after(None));
+    }
+
+    #[test]
+    fn members_change_rows() {
+        let conflict = Conflict {
+            parties: vec![
+                Party::new(0, vec![member(0, 1), member(1, 1)]),
+                Party::new(1, vec![member(0, 1)]),
+            ],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: true,
+        };
+
+        let state = conflict
+            .apply(0, &AppliedAction::Move(participant(0, 1)))
+            .unwrap();
+        assert_eq!(state.parties[0].members[1].row, Row::Back);
+        assert!(!state.parties[0].is_within_reach(&state.parties[0].members[1]));
+
+        // Without anyone left in front, the back row is exposed.
+        let state = state.apply(1, &AppliedAction::Pass).unwrap();
+        let state = state
+            .apply(0, &AppliedAction::Move(participant(0, 0)))
+            .unwrap();
+        assert!(state.parties[0].is_within_reach(&state.parties[0].members[1]));
+    }
+
+    #[test]
+    fn guards_intercept_attacks() {
+        let conflict = Conflict {
+            parties: vec![
+                Party::new(0, vec![member(0, 1), member(1, 1)]),
+                Party::new(1, vec![member(0, 1)]),
+            ],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
+        };
+
+        let targeted = |action, source, target| {
+            AppliedAction::Targeted(TargetedAction {
+                action,
+                source,
+                target,
+                cost: Cost::default(),
+            })
+        };
+        let attack = |source, target| {
+            let attack = Action::SimpleAttack(SimpleAttackAction {
+                weapon: None,
+                damage: 4.0,
+                roll: AttackRoll::Hit,
+            });
+            targeted(attack, source, target)
+        };
+
+        let guard = targeted(Action::Guard, participant(0, 0), participant(0, 1));
+        let state = conflict.apply(0, &guard).unwrap();
+        assert_eq!(state.guard_of(&participant(0, 1)), Some(participant(0, 0)));
+        assert_eq!(state.guard_of(&participant(0, 0)), None);
+
+        // The guard takes the hit in place of the protected ally.
+        let state = state
+            .apply(1, &attack(participant(1, 0), participant(0, 1)))
+            .unwrap();
+        assert_eq!(state.parties[0].members[0].health, 6.0);
+        assert_eq!(state.parties[0].members[1].health, 10.0);
+
+        // The stance lasts while other members of the party act...
+        let state = state
+            .apply(0, &attack(participant(0, 1), participant(1, 0)))
+            .unwrap();
+        let state = state.apply(1, &AppliedAction::Pass).unwrap();
+        assert_eq!(state.guard_of(&participant(0, 1)), Some(participant(0, 0)));
+
+        // ... until the guard acts again.
+        let state = state
+            .apply(0, &attack(participant(0, 0), participant(1, 0)))
+            .unwrap();
+        assert_eq!(state.guard_of(&participant(0, 1)), None);
+        let state = state
+            .apply(1, &attack(participant(1, 0), participant(0, 1)))
+            .unwrap();
+        assert_eq!(state.parties[0].members[1].health, 6.0);
+    }
+
+    #[test]
+    fn members_escape_one_at_a_time() {
+        let mut conflict = Conflict {
+            parties: vec![
+                Party::new(0, vec![member(0, 1), member(1, 1), member(2, 1)]),
+                Party::new(1, vec![member(0, 1)]),
+            ],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
+        };
+        conflict.parties[0].can_retreat = true;
+        conflict.parties[0].escape_chance = Some(0.5);
+
+        let escape = |member_id: usize| {
+            let actions = conflict.actions().unwrap();
+            let escapes: Vec<_> = actions
+                .filter_map(|action| match action {
+                    AppliedAction::Escape(escape) => Some(escape),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(escapes.len(), 3);
+            AppliedAction::Escape(escapes[member_id].clone())
+        };
+
+        // Getting away is left to chance; failing costs the turn.
+        let outcomes = escape(0).outcomes();
+        assert_eq!(outcomes.len(), 2);
+        let (_, failed) = &outcomes[1];
+        let state = conflict.apply(0, failed).unwrap();
+        assert!(!state.parties[0].members[0].escaped);
+
+        // Members who got away are out of reach, while the rest fights on.
+        let state = conflict.apply(0, &escape(0)).unwrap();
+        assert!(state.parties[0].members[0].escaped);
+        assert!(!state.parties[0].has_retreated());
+        assert!(!state.is_decided());
+        assert!(state
+            .actions()
+            .unwrap()
+            .flat_map(|action| action.targets().to_vec())
+            .all(|target| target != participant(0, 0)));
+
+        // The party has retreated once all living members are gone.
+        let mut state = state.apply(1, &AppliedAction::Pass).unwrap();
+        state.parties[0].members[1].health = 0.0;
+        let state = state.apply(0, &escape(2)).unwrap();
+        assert!(state.parties[0].has_retreated());
+        assert!(!state.parties[0].is_defeated());
+        assert!(state.has_ended_after(Some(&escape(2))));
+    }
 }
@@ -0,0 +1,181 @@
+use crate::solver::{serialize_seconds, Event, Outcome, OutcomeType, PartyResult};
+use serde::Serialize;
+use std::io::Write;
+use std::time::Duration;
+
+/// A single record of the JSON Lines export.
+#[derive(Serialize)]
+#[serde(tag = "record", rename_all = "snake_case")]
+enum Record<'a> {
+    /// The outcome and search statistics. Always the first record.
+    Summary(Summary<'a>),
+    /// An event of the timeline, in order.
+    Event(&'a Event),
+}
+
+/// The outcome of a conflict without its timeline.
+#[derive(Serialize)]
+struct Summary<'a> {
+    outcome: &'a OutcomeType,
+    results: &'a [PartyResult],
+    evaluations: usize,
+    cuts: usize,
+    transposition_hits: usize,
+    win_probability: f32,
+    #[serde(serialize_with = "serialize_seconds")]
+    search_duration: Duration,
+    max_visited_depth: usize,
+    search_depth: usize,
+    depth_limited: bool,
+    /// The number of events following the summary.
+    turns: usize,
+}
+
+/// Writes the outcome, including its timeline, as a single JSON document.
+pub fn write_json<W: Write>(outcome: &Outcome, writer: W) -> serde_json::Result<()> {
+    serde_json::to_writer_pretty(writer, outcome)
+}
+
+/// Writes the outcome as JSON Lines: a summary record with the search statistics,
+/// followed by one record per timeline event.
+pub fn write_json_lines<W: Write>(outcome: &Outcome, mut writer: W) -> serde_json::Result<()> {
+    let summary = Record::Summary(Summary {
+        outcome: &outcome.outcome,
+        results: &outcome.results,
+        evaluations: outcome.evaluations,
+        cuts: outcome.cuts,
+        transposition_hits: outcome.transposition_hits,
+        win_probability: outcome.win_probability,
+        search_duration: outcome.search_duration,
+        max_visited_depth: outcome.max_visited_depth,
+        search_depth: outcome.search_depth,
+        depth_limited: outcome.depth_limited,
+        turns: outcome.len(),
+    });
+
+    write_line(&mut writer, &summary)?;
+    for event in &outcome.timeline {
+        write_line(&mut writer, &Record::Event(event))?;
+    }
+
+    Ok(())
+}
+
+/// Writes a single record, terminated by a newline.
+fn write_line<W: Write>(writer: &mut W, record: &Record) -> serde_json::Result<()> {
+    serde_json::to_writer(&mut *writer, record)?;
+    writer.write_all(b"\n").map_err(serde_json::Error::io)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conflict::{Conflict, TurnOrder};
+    use crate::party::Party;
+    use crate::party_member::{PartyMember, ResourcePool, Row};
+    use crate::solver::{Solver, SolverStrategy};
+    use crate::utility_value::Personality;
+    use crate::weapon::Weapon;
+    use serde_json::Value;
+
+    fn solve_duel() -> Outcome {
+        let member = PartyMember {
+            id: 0,
+            health: 10.0,
+            max_health: 10.0,
+            damage_taken: 0.0,
+            damage_mitigated: 0.0,
+            weapon: Weapon {
+                name: "their fists".to_string(),
+                damage: 10.0,
+                ..Default::default()
+            },
+            can_act: true,
+            speed: 0,
+            row: Row::Front,
+            stance: None,
+            escaped: false,
+            armor: 0.0,
+            resistances: Vec::default(),
+            damage_bonus: 0.0,
+            abilities: Vec::default(),
+            mana: ResourcePool::default(),
+            stamina: ResourcePool::default(),
+            spells: Vec::default(),
+            effects: Vec::default(),
+        };
+
+        let conflict = Conflict {
+            parties: vec![
+                Party {
+                    id: 0,
+                    members: vec![member.clone()],
+                    can_retreat: false,
+                    retreated: false,
+                    alliance: 0,
+                    escape_chance: None,
+                    personality: None,
+                },
+                Party {
+                    id: 1,
+                    members: vec![member],
+                    can_retreat: false,
+                    retreated: false,
+                    alliance: 1,
+                    escape_chance: None,
+                    personality: None,
+                },
+            ],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
+        };
+
+        Solver::engage(
+            &conflict,
+            SolverStrategy::DepthLimited(10),
+            &Personality::default(),
+        )
+    }
+
+    #[test]
+    fn json_export_works() {
+        let outcome = solve_duel();
+        let mut buffer = Vec::new();
+        write_json(&outcome, &mut buffer).unwrap();
+
+        let json: Value = serde_json::from_slice(&buffer).unwrap();
+        assert_eq!(json["outcome"]["type"], "win");
+        assert_eq!(json["outcome"]["score"], 10.0);
+        assert_eq!(json["timeline"][0]["party_id"], 0);
+        assert_eq!(json["timeline"][0]["is_initiator_turn"], true);
+        assert_eq!(json["timeline"][0]["action"]["type"], "targeted");
+        assert_eq!(
+            json["timeline"][0]["action"]["action"]["weapon"]["name"],
+            "their fists"
+        );
+        assert_eq!(
+            json["timeline"][0]["state"]["parties"][1]["members"][0]["health"],
+            0.0
+        );
+    }
+
+    #[test]
+    fn json_lines_export_works() {
+        let outcome = solve_duel();
+        let mut buffer = Vec::new();
+        write_json_lines(&outcome, &mut buffer).unwrap();
+
+        let records: Vec<Value> = String::from_utf8(buffer)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(records.len(), 1 + outcome.len());
+        assert_eq!(records[0]["record"], "summary");
+        assert_eq!(records[0]["turns"], outcome.len());
+        assert_eq!(records[0]["results"][1]["outcome"], "defeated");
+        assert_eq!(records[1]["record"], "event");
+        assert_eq!(records[1]["turn"], 1);
+    }
+}
@@ -0,0 +1,64 @@
+//! Predicts the outcome of turn-based encounters between two or more parties,
+//! as found in role-playing games, by searching for the best actions of every side.
+//!
+//! Encounters are described by a [`Conflict`], either built directly from [`Party`] and
+//! [`PartyMember`] values or loaded from a [`Scenario`] file. The [`Solver`] then resolves
+//! the whole encounter into an [`Outcome`] with a timeline of events, or advises on the
+//! actions available in the current state, one turn at a time:
+//!
+//! ```
+//! use autobattler::{Personality, Scenario, Solver, SolverStrategy, WeaponCatalog};
+//!
+//! let scenario = Scenario::from_toml(
+//!     r#"
+//!     [initiator]
+//!
+//!     [[initiator.members]]
+//!     health = 20
+//!     weapon = { type = "fists", damage = 10 }
+//!
+//!     [opponent]
+//!
+//!     [[opponent.members]]
+//!     health = 10
+//!     weapon = { type = "stick", damage = 5 }
+//!     "#,
+//!     &WeaponCatalog::builtin(),
+//! )
+//! .unwrap();
+//!
+//! let strategy = SolverStrategy::IterativeDeepening(10);
+//! let advice = Solver::advise(&scenario.conflict, strategy, &Personality::default());
+//! let party_id = scenario.conflict.acting_party().id;
+//! let next = scenario.conflict.apply(party_id, &advice[0].action).unwrap();
+//! assert!(next.parties[1].is_defeated());
+//! ```
+
+pub mod action;
+pub mod action_iterator;
+pub mod catalog;
+pub mod conflict;
+pub mod export;
+mod max_n;
+mod monte_carlo;
+mod move_ordering;
+pub mod party;
+pub mod party_member;
+pub mod scenario;
+pub mod solver;
+pub mod spell;
+pub mod status_effect;
+mod transposition;
+pub mod utility_value;
+pub mod value;
+pub mod weapon;
+
+pub use action::{Action, AppliedAction};
+pub use catalog::WeaponCatalog;
+pub use conflict::{Conflict, TurnOrder};
+pub use party::{Participant, Party};
+pub use party_member::PartyMember;
+pub use scenario::{Scenario, ScenarioError};
+pub use solver::{Advice, Event, Outcome, OutcomeType, PartyResult, Solver, SolverStrategy};
+pub use utility_value::{Evaluator, Personality};
+pub use weapon::Weapon;
@@ -1,83 +1,211 @@
-use crate::action::{Action, AppliedAction};
-use crate::conflict::Conflict;
-use crate::party::{Participant, Party};
-use crate::party_member::PartyMember;
-use crate::solver::{OutcomeType, Solver, SolverStrategy};
-use crate::weapon::{Fists, Stick, Weapon};
+use autobattler::action::{Action, AppliedAction, AttackRoll, Cost, SimpleAttackAction};
+use autobattler::catalog::WeaponCatalog;
+use autobattler::conflict::{Conflict, TurnOrder};
+use autobattler::export;
+use autobattler::party::Participant;
+use autobattler::party_member::{PartyMember, Row, Stance};
+use autobattler::scenario::Scenario;
+use autobattler::solver::{
+    Advice, Event, OutcomeType, PartyOutcome, PartyResult, Solver, SolverStrategy,
+};
+use autobattler::utility_value::{Evaluator, Personality};
+use autobattler::weapon::WeaponArea;
+use clap::{Parser, ValueEnum};
 use colored::{ColoredString, Colorize};
+use rand::seq::SliceRandom;
 use rnglib::{Language, RNG};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
 
-mod action;
-mod action_iterator;
-mod conflict;
-mod party;
-mod party_member;
-mod solver;
-mod utility_value;
-mod value;
-mod weapon;
+/// The encounter used when no scenario file is provided.
+const DEFAULT_SCENARIO: &str = include_str!("../scenarios/default.toml");
+
+/// Predicts the outcome of a turn-based encounter between two or more parties.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Args {
+    /// The scenario file describing the encounter, either `.toml` or `.json`.
+    /// If omitted, a built-in example encounter is used.
+    scenario: Option<PathBuf>,
+    /// A weapon catalog, either `.toml` or `.json`, adding to or replacing
+    /// the built-in weapons. May be given multiple times.
+    #[arg(long)]
+    catalog: Vec<PathBuf>,
+    /// The format in which the outcome is printed.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+    /// The search algorithm used to resolve the encounter.
+    #[arg(long, value_enum, default_value_t = Strategy::Minimax)]
+    strategy: Strategy,
+    /// The maximum search depth of minimax.
+    #[arg(long, default_value_t = 10)]
+    max_depth: usize,
+    /// The time budget of minimax in seconds. If set, the search deepens
+    /// until time runs out instead of stopping at the maximum depth.
+    #[arg(long, value_parser = parse_seconds)]
+    time_limit: Option<Duration>,
+    /// The number of threads sharing the work of minimax.
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+    threads: u16,
+    /// The number of playouts of Monte Carlo Tree Search.
+    #[arg(long, default_value_t = 10_000)]
+    iterations: usize,
+    /// The exploration constant of Monte Carlo Tree Search.
+    #[arg(long, default_value_t = std::f32::consts::SQRT_2)]
+    exploration: f32,
+    /// How the attacking side weighs the possible outcomes of the encounter.
+    #[arg(long, value_enum, default_value_t = Temperament::Standard)]
+    personality: Temperament,
+    /// Play the encounter interactively, choosing the actions of the party with this ID
+    /// while the solver acts for everyone else. The attacking side has ID 0.
+    #[arg(long, value_name = "PARTY_ID")]
+    play: Option<usize>,
+}
+
+/// How the attacking side weighs the possible outcomes of the encounter.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Temperament {
+    /// Keeps as much health as possible; retreats only reluctantly.
+    Standard,
+    /// Values damage dealt to the opponents as much as health kept.
+    Aggressive,
+    /// Preserves health, happy to retreat or to see the opponents leave.
+    Cautious,
+}
+
+/// The search algorithm used to resolve the encounter.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Strategy {
+    /// Exhaustive minimax search with iterative deepening.
+    Minimax,
+    /// Monte Carlo Tree Search; scales to larger encounters.
+    MonteCarlo,
+}
+
+/// The format in which the outcome is printed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// A colored, human-readable description of the encounter.
+    Text,
+    /// A single JSON document containing the outcome and its timeline.
+    Json,
+    /// JSON Lines: a summary record followed by one record per event.
+    JsonLines,
+}
 
 fn main() {
     env_logger::init();
+    let args = Args::parse();
+
+    let mut catalog = WeaponCatalog::builtin();
+    for path in &args.catalog {
+        match WeaponCatalog::load(path) {
+            Ok(loaded) => catalog.extend(loaded),
+            Err(error) => {
+                eprintln!("{} {}", "error:".red(), error);
+                std::process::exit(1);
+            }
+        }
+    }
 
-    let heroes = Party {
-        id: 0,
-        members: vec![PartyMember {
-            id: 0,
-            health: 20.0,
-            damage_taken: 0.0,
-            weapon: Weapon::Fists(Fists { damage: 10.0 }),
-            can_act: true,
-        }],
-        can_retreat: true,
-        retreated: false,
+    let scenario = match &args.scenario {
+        Some(path) => Scenario::load(path, &catalog),
+        None => Scenario::from_toml(DEFAULT_SCENARIO, &catalog),
     };
 
-    let rng = RNG::try_from(&Language::Fantasy).unwrap();
-    let hero_names = rng.generate_names(heroes.len(), false);
-
-    let villains = Party {
-        id: 1,
-        members: vec![
-            PartyMember {
-                id: 0,
-                health: 15.0,
-                damage_taken: 0.0,
-                weapon: Weapon::Stick(Stick { damage: 5.0 }),
-                can_act: true,
-            },
-            PartyMember {
-                id: 1,
-                health: 10.0,
-                damage_taken: 0.0,
-                weapon: Weapon::Fists(Fists { damage: 20.0 }),
-                can_act: true,
-            },
-        ],
-        can_retreat: true,
-        retreated: false,
+    let scenario = match scenario {
+        Ok(scenario) => scenario,
+        Err(error) => {
+            eprintln!("{} {}", "error:".red(), error);
+            std::process::exit(1);
+        }
+    };
+
+    let conflict = scenario.conflict;
+
+    // Names of the initiator and its allies sound different from those of everyone else.
+    let names: Vec<_> = conflict
+        .parties
+        .iter()
+        .zip(&scenario.names)
+        .map(|(party, names)| {
+            if party.is_allied_with(conflict.initiator()) {
+                complete_names(names, &Language::Fantasy)
+            } else {
+                complete_names(names, &Language::Demonic)
+            }
+        })
+        .collect();
+
+    let strategy = match args.strategy {
+        Strategy::Minimax => match args.time_limit {
+            Some(budget) => SolverStrategy::TimeLimited(budget),
+            None => SolverStrategy::IterativeDeepening(args.max_depth),
+        },
+        Strategy::MonteCarlo => SolverStrategy::MonteCarlo {
+            iterations: args.iterations,
+            exploration: args.exploration,
+        },
+    };
+
+    let personality = match args.personality {
+        Temperament::Standard => Personality::default(),
+        Temperament::Aggressive => Personality::aggressive(),
+        Temperament::Cautious => Personality::cautious(),
     };
 
-    let rng = RNG::try_from(&Language::Demonic).unwrap();
-    let villain_names = rng.generate_names(villains.len(), false);
+    if let Some(party_id) = args.play {
+        if !conflict.parties.iter().any(|party| party.id == party_id) {
+            eprintln!("{} there is no party with ID {}", "error:".red(), party_id);
+            std::process::exit(1);
+        }
+        if args.format != OutputFormat::Text {
+            eprintln!(
+                "{} interactive play requires the text format",
+                "error:".red()
+            );
+            std::process::exit(1);
+        }
+
+        play(&conflict, party_id, strategy, &personality, &names);
+        return;
+    }
 
-    let names = vec![hero_names, villain_names];
+    let outcome =
+        Solver::engage_parallel(&conflict, strategy, &personality, usize::from(args.threads));
 
-    let conflict = Conflict {
-        initiator: heroes,
-        opponent: villains,
+    let stdout = std::io::stdout().lock();
+    let exported = match args.format {
+        OutputFormat::Text => Ok(()),
+        OutputFormat::Json => export::write_json(&outcome, stdout).map(|_| println!()),
+        OutputFormat::JsonLines => export::write_json_lines(&outcome, stdout),
     };
 
-    let outcome = Solver::engage(&conflict, SolverStrategy::IterativeDeepening(10));
+    if let Err(error) = exported {
+        eprintln!("{} unable to export outcome: {}", "error:".red(), error);
+        std::process::exit(1);
+    }
+
+    if args.format != OutputFormat::Text {
+        return;
+    }
 
     println!(
-        "Performed {} evaluations with {} cuts at depth {} in {:?}. The encounter has {} turns.",
+        "Performed {} evaluations with {} cuts and {} transposition hits at depth {} in {:?}. The encounter has {} turns.",
         outcome.evaluations,
         outcome.cuts,
+        outcome.transposition_hits,
         outcome.max_visited_depth,
         outcome.search_duration,
         outcome.len()
     );
+    if args.strategy == Strategy::Minimax && args.time_limit.is_some() {
+        println!(
+            "Searched up to depth {} within the time limit.",
+            outcome.search_depth
+        );
+    }
     match outcome.outcome {
         OutcomeType::Win(score) => println!(
             "{} {} with a score of {}.",
@@ -109,78 +237,801 @@ fn main() {
             "Anything could happen".white(),
             score
         ),
+        OutcomeType::Expected(score) => println!(
+            "{} {} with an expected score of {} and a {:.1}% chance of winning.",
+            "TL;DR:".bright_white(),
+            "It comes down to luck".yellow(),
+            score,
+            outcome.win_probability * 100.0
+        ),
+    }
+
+    if conflict.parties.len() > 2 {
+        print_results(&conflict, &outcome.results);
     }
 
-    println!("\n{}", "On the attacking side:".bright_white());
-    for member in &conflict.initiator.members {
-        let name = &names[conflict.initiator.id][member.id];
+    let initiator_side = initiator_side(&conflict);
+    print_parties(&conflict, &initiator_side, &names);
+
+    let mut previous = conflict.clone();
+    for event in outcome.timeline {
+        let likelihood = if event.probability < 1.0 {
+            format!(", {:.1}% likely", event.probability * 100.0)
+        } else {
+            String::default()
+        };
         println!(
-            "- {}, with {} health and {}",
-            name.blue(),
-            member.health,
-            format!("{:#?}", member.weapon).yellow()
+            "\nTurn {} (discovered at step {}{}):",
+            format!("{}", event.turn).bright_white(),
+            event.depth,
+            likelihood
         );
+
+        print_event(&previous, &event, &initiator_side, &names);
+        previous = event.state;
     }
+}
 
-    println!("\n{}", "On the defending side:".bright_white());
-    for member in &conflict.opponent.members {
-        let name = &names[conflict.opponent.id][member.id];
+/// Plays the encounter turn by turn: the player chooses the actions of one party,
+/// the solver those of all others, and random results are rolled as they happen.
+///
+/// ## Arguments
+/// * `conflict` - The conflict situation to play out.
+/// * `player` - The ID of the party controlled by the player.
+/// * `strategy` - The search algorithm the solver uses on every turn.
+/// * `evaluator` - Scores the states of the conflict in the eyes of the initiating party.
+/// * `names` - The names of all members, by party and member ID.
+fn play(
+    conflict: &Conflict,
+    player: usize,
+    strategy: SolverStrategy,
+    evaluator: &dyn Evaluator,
+    names: &[Vec<String>],
+) {
+    let initiator_side = initiator_side(conflict);
+    let mut rng = rand::thread_rng();
+    let mut state = conflict.clone();
+    let mut last_action = None;
+    for turn in 1.. {
+        if state.has_ended_after(last_action.as_ref()) {
+            break;
+        }
+
+        // Both the player and the solver choose among the same advice, best first.
+        let party_id = state.acting_party().id;
+        let advice = Solver::advise(&state, strategy, evaluator);
+        if advice.is_empty() {
+            break;
+        }
+
+        let action = if party_id == player {
+            print_parties(&state, &initiator_side, names);
+            match choose_action(&state, &advice, &initiator_side, names) {
+                Some(action) => action,
+                None => return,
+            }
+        } else {
+            advice[0].action.clone()
+        };
+
+        let (probability, action) = action
+            .outcomes()
+            .choose_weighted(&mut rng, |(probability, _)| *probability)
+            .expect("Every action has a result")
+            .clone();
+        let next = state
+            .apply(party_id, &action)
+            .expect("The results of applicable actions apply");
+
+        let likelihood = if probability < 1.0 {
+            format!(" ({:.1}% likely)", probability * 100.0)
+        } else {
+            String::default()
+        };
         println!(
-            "- {}, with {} health and {}",
-            name.purple(),
-            member.health,
-            format!("{:#?}", member.weapon).yellow()
+            "\nTurn {}{}:",
+            format!("{}", turn).bright_white(),
+            likelihood
         );
+
+        let event = Event {
+            turn,
+            party_id,
+            is_initiator_turn: state.is_initiator_turn(),
+            action: action.clone(),
+            probability,
+            depth: turn,
+            state: next,
+        };
+        print_event(&state, &event, &initiator_side, names);
+        state = event.state;
+        last_action = Some(action);
     }
 
-    let initiator_party = conflict.initiator.id;
-    for event in outcome.timeline {
+    print_results(&state, &PartyResult::from_state(&state));
+}
+
+/// Asks the player to choose one of the actions available to their party.
+///
+/// ## Returns
+/// The chosen action, or [`None`] if the player quits.
+fn choose_action(
+    state: &Conflict,
+    advice: &[Advice],
+    initiator_side: &[usize],
+    names: &[Vec<String>],
+) -> Option<AppliedAction> {
+    println!("\n{}", "Your options, best first:".bright_white());
+    for (index, advice) in advice.iter().enumerate() {
         println!(
-            "\nTurn {} (discovered at step {}):",
-            format!("{}", event.turn).bright_white(),
-            event.depth
+            "  {}) {}",
+            index + 1,
+            describe_option(state, &advice.action, initiator_side, names)
+        );
+    }
+
+    let stdin = std::io::stdin();
+    loop {
+        print!("Choose an action (1-{}, or q to quit): ", advice.len());
+        std::io::stdout().flush().ok()?;
+
+        let mut line = String::default();
+        if stdin.read_line(&mut line).ok()? == 0 {
+            return None;
+        }
+
+        match line.trim() {
+            "q" | "quit" => return None,
+            choice => match choice.parse::<usize>() {
+                Ok(index) if (1..=advice.len()).contains(&index) => {
+                    return Some(advice[index - 1].action.clone())
+                }
+                _ => println!("{}", "Please enter the number of an option.".yellow()),
+            },
+        }
+    }
+}
+
+/// Describes an action the player may choose, before any of its results are rolled.
+fn describe_option(
+    state: &Conflict,
+    action: &AppliedAction,
+    initiator_side: &[usize],
+    names: &[Vec<String>],
+) -> String {
+    let cost = |cost: &Cost| {
+        if cost.is_free() {
+            String::default()
+        } else {
+            format!(" for {}", cost.to_string().bright_blue())
+        }
+    };
+
+    match action {
+        AppliedAction::Flee => format!("{} flees", describe_party(state, state.acting_party().id)),
+        AppliedAction::Pass => "pass".to_string(),
+        AppliedAction::Move(member) => format!(
+            "{} moves to the {} row",
+            color_participant(initiator_side, names, member),
+            match state.targeted_member(member).row {
+                Row::Front => row_name(Row::Back),
+                Row::Back => row_name(Row::Front),
+            }
+        ),
+        AppliedAction::Escape(escape) => format!(
+            "{} tries to get away ({:.0}% chance)",
+            color_participant(initiator_side, names, &escape.member),
+            escape.chance * 100.0
+        ),
+        AppliedAction::Targeted(action) => format!(
+            "{} {}{}",
+            color_participant(initiator_side, names, &action.source),
+            describe_intent(
+                &action.action,
+                &color_participant(initiator_side, names, &action.target).to_string()
+            ),
+            cost(&action.cost)
+        ),
+        AppliedAction::Area(action) => {
+            let targets: Vec<_> = action
+                .targets
+                .iter()
+                .map(|target| color_participant(initiator_side, names, target).to_string())
+                .collect();
+            format!(
+                "{} {}{}",
+                color_participant(initiator_side, names, &action.source),
+                describe_intent(&action.action, &targets.join(", ")),
+                cost(&action.cost)
+            )
+        }
+    }
+}
+
+/// Describes what an action does to its targets, e.g. "attacks Rask with a stick".
+fn describe_intent(action: &Action, targets: &str) -> String {
+    match action {
+        Action::SimpleAttack(attack) => {
+            format!(
+                "attacks {} with {}",
+                targets,
+                format!("{:?}", attack).yellow()
+            )
+        }
+        Action::Heal(heal) => format!(
+            "tends to {}, restoring up to {} health",
+            targets, heal.amount
+        ),
+        Action::Buff(buff) => format!(
+            "cheers {} on, adding {} damage to their attacks",
+            targets, buff.damage_bonus
+        ),
+        Action::StatusEffect(effect) => {
+            format!("leaves {} {}", targets, effect.to_string().green())
+        }
+        Action::Spell(spell) => format!("casts {} on {}", spell.name.magenta(), targets),
+        Action::Defend(defend) => format!(
+            "takes a defensive stance, taking {} times the damage until their next turn",
+            defend.damage_multiplier
+        ),
+        Action::Guard => format!("guards {}, intercepting attacks on them", targets),
+    }
+}
+
+/// Lists the parties whose members are shown as allies of the initiating party.
+fn initiator_side(conflict: &Conflict) -> Vec<usize> {
+    conflict
+        .parties
+        .iter()
+        .filter(|party| party.is_allied_with(conflict.initiator()))
+        .map(|party| party.id)
+        .collect()
+}
+
+/// Prints every party of the conflict along with its members and, if members act by
+/// initiative, the order in which they act.
+fn print_parties(conflict: &Conflict, initiator_side: &[usize], names: &[Vec<String>]) {
+    for (index, party) in conflict.parties.iter().enumerate() {
+        let heading = match index {
+            0 => "On the attacking side:".to_string(),
+            1 => "On the defending side:".to_string(),
+            _ if party.is_allied_with(conflict.initiator()) => {
+                "Allied with the attacking side:".to_string()
+            }
+            _ if party.is_allied_with(&conflict.parties[1]) => {
+                "Allied with the defending side:".to_string()
+            }
+            _ => format!("Also involved as party {}:", party.id),
+        };
+
+        println!("\n{}", heading.bright_white());
+        for member in &party.members {
+            let participant = Participant {
+                party_id: party.id,
+                member_id: member.id,
+            };
+            println!(
+                "- {}{}, with {} health{} and {}{}{}{}",
+                color_participant(initiator_side, names, &participant),
+                describe_row(conflict, member),
+                member.health,
+                describe_resources(member),
+                format!("{:#?}", member.weapon).yellow(),
+                describe_defenses(member),
+                describe_abilities(member),
+                describe_spells(member)
+            );
+        }
+    }
+
+    if let TurnOrder::Initiative { .. } = conflict.turn_order {
+        let queue: Vec<_> = conflict
+            .initiative_queue()
+            .iter()
+            .map(|participant| color_participant(initiator_side, names, participant).to_string())
+            .collect();
+        println!(
+            "\n{} {}",
+            "Members act by initiative:".bright_white(),
+            queue.join(", ")
+        );
+    }
+}
+
+/// Prints how each party fared.
+fn print_results(conflict: &Conflict, results: &[PartyResult]) {
+    println!("\n{}", "Per party:".bright_white());
+    for result in results {
+        let outcome = match result.outcome {
+            PartyOutcome::Victorious => "victorious".green(),
+            PartyOutcome::Defeated => "defeated".red(),
+            PartyOutcome::Retreated => "retreated".yellow(),
+            PartyOutcome::Fighting => "still fighting".white(),
+        };
+        println!(
+            "- {} is {}",
+            describe_party(conflict, result.party_id),
+            outcome
         );
+    }
+}
+
+/// Prints what happened in an event, given the state of the conflict before it.
+fn print_event(
+    previous: &Conflict,
+    event: &Event,
+    initiator_side: &[usize],
+    names: &[Vec<String>],
+) {
+    match &event.action {
+        AppliedAction::Flee => {
+            println!("  {} flees", describe_party(previous, event.party_id))
+        }
+        AppliedAction::Escape(escape) if escape.escaped => println!(
+            "  {} {}",
+            color_participant(initiator_side, names, &escape.member),
+            "gets away".cyan()
+        ),
+        AppliedAction::Escape(escape) => println!(
+            "  {} tries to get away but fails",
+            color_participant(initiator_side, names, &escape.member)
+        ),
+        AppliedAction::Move(member) => println!(
+            "  {} moves to the {} row",
+            color_participant(initiator_side, names, member),
+            row_name(event.state.targeted_member(member).row)
+        ),
+        AppliedAction::Pass => match previous.next_actor() {
+            Some((_, actor)) => println!(
+                "  {} is unable to act",
+                color_participant(initiator_side, names, &actor)
+            ),
+            None => println!(
+                "  {} is unable to act",
+                describe_party(previous, event.party_id)
+            ),
+        },
+        AppliedAction::Targeted(action) => {
+            let source = color_participant(initiator_side, names, &action.source);
+            let target = color_participant(initiator_side, names, &action.target);
+
+            // Guards intercept attacks on the allies they protect.
+            let guard = previous
+                .guard_of(&action.target)
+                .filter(|_| !action.action.targets_allies());
+            let hit = guard.clone().unwrap_or_else(|| action.target.clone());
+            match &action.action {
+                Action::SimpleAttack(attack) => match attack.roll {
+                    AttackRoll::Hit => println!(
+                        "  {} whacks {} with {}, dealing {}",
+                        source,
+                        target,
+                        format!("{:?}", attack).yellow(),
+                        describe_damage(previous.targeted_member(&hit), attack)
+                    ),
+                    AttackRoll::Critical => println!(
+                        "  {} {} {} with {}, dealing {}",
+                        source,
+                        "critically whacks".bright_red(),
+                        target,
+                        format!("{:?}", attack).yellow(),
+                        describe_damage(previous.targeted_member(&hit), attack)
+                    ),
+                    AttackRoll::Miss => println!(
+                        "  {} swings {} at {} but misses",
+                        source,
+                        format!("{:?}", attack).yellow(),
+                        target
+                    ),
+                },
+                Action::Heal(heal) => println!(
+                    "  {} tends to {}, restoring up to {} health",
+                    source, target, heal.amount
+                ),
+                Action::Buff(buff) => println!(
+                    "  {} cheers {} on, adding {} damage to their attacks",
+                    source, target, buff.damage_bonus
+                ),
+                Action::StatusEffect(effect) => println!(
+                    "  {} leaves {} {}",
+                    source,
+                    target,
+                    format!("{}", effect).green()
+                ),
+                Action::Spell(spell) => println!(
+                    "  {} casts {} on {}{}",
+                    source,
+                    spell.name.magenta(),
+                    target,
+                    describe_spell_effect(previous.targeted_member(&hit), &spell.effect)
+                ),
+                Action::Defend(defend) => println!(
+                    "  {} takes a defensive stance, taking {} times the damage until their next turn",
+                    source, defend.damage_multiplier
+                ),
+                Action::Guard => println!(
+                    "  {} guards {}, intercepting attacks on them",
+                    source, target
+                ),
+            };
+
+            if let Some(guard) = &guard {
+                println!(
+                    "   ⇒ {} {} {}",
+                    color_participant(initiator_side, names, guard),
+                    "intercepts the attack on".cyan(),
+                    target
+                );
+            }
+
+            let target = event.state.targeted_member(&hit);
+            if target.is_dead() {
+                println!(
+                    "   ⇒ {} has {}",
+                    color_participant(initiator_side, names, &hit),
+                    "given up on being alive".red()
+                );
+            } else {
+                println!(
+                    "   ⇒ {} now has {} health{}",
+                    color_participant(initiator_side, names, &hit),
+                    target.health,
+                    describe_effects(target)
+                );
+            }
+
+            if !action.cost.is_free() {
+                println!(
+                    "   ⇒ {} {}",
+                    source,
+                    describe_spending(previous.targeted_member(&action.source), &action.cost)
+                );
+            }
+        }
+        AppliedAction::Area(action) => {
+            let source = color_participant(initiator_side, names, &action.source);
+            let targets: Vec<_> = action
+                .targets
+                .iter()
+                .map(|target| color_participant(initiator_side, names, target).to_string())
+                .collect();
+            let targets = targets.join(", ");
+            match &action.action {
+                Action::SimpleAttack(attack) => match attack.roll {
+                    AttackRoll::Hit => println!(
+                        "  {} whacks {} with {}",
+                        source,
+                        targets,
+                        format!("{:?}", attack).yellow()
+                    ),
+                    AttackRoll::Critical => println!(
+                        "  {} {} {} with {}",
+                        source,
+                        "critically whacks".bright_red(),
+                        targets,
+                        format!("{:?}", attack).yellow()
+                    ),
+                    AttackRoll::Miss => println!(
+                        "  {} swings {} at {} but misses",
+                        source,
+                        format!("{:?}", attack).yellow(),
+                        targets
+                    ),
+                },
+                Action::Spell(spell) => println!(
+                    "  {} casts {} on {}{}",
+                    source,
+                    spell.name.magenta(),
+                    targets,
+                    match spell.effect.effect() {
+                        Action::SimpleAttack(attack) if attack.roll == AttackRoll::Miss => {
+                            ", but it misses"
+                        }
+                        Action::SimpleAttack(attack) if attack.roll == AttackRoll::Critical => {
+                            ", hitting critically"
+                        }
+                        _ => "",
+                    }
+                ),
+                action => println!("  {} uses {:?} on {}", source, action, targets),
+            };
 
-        match event.action {
-            AppliedAction::Flee => println!("  the party flees"),
-            AppliedAction::Targeted(action) => {
-                match action.action {
-                    Action::SimpleAttack(attack) => {
-                        println!(
-                            "  {} whacks {} with {}, dealing {} damage",
-                            color_participant(initiator_party, &names, &action.source),
-                            color_participant(initiator_party, &names, &action.target),
-                            format!("{:?}", attack).yellow(),
-                            attack.damage
-                        );
+            for participant in &action.targets {
+                let name = color_participant(initiator_side, names, participant);
+                let target = event.state.targeted_member(participant);
+                let damage = match action.action.effect() {
+                    Action::SimpleAttack(attack) if attack.roll != AttackRoll::Miss => {
+                        let damage = describe_damage(previous.targeted_member(participant), attack);
+                        format!("takes {damage} and ")
                     }
+                    _ => String::default(),
                 };
 
-                let target = event.state.targeted_member(&action.target);
                 if target.is_dead() {
                     println!(
-                        "   ⇒ {} has {}",
-                        color_participant(initiator_party, &names, &action.target,),
+                        "   ⇒ {} {}has {}",
+                        name,
+                        damage,
                         "given up on being alive".red()
                     );
                 } else {
                     println!(
-                        "   ⇒ {} now has {} health",
-                        color_participant(initiator_party, &names, &action.target,),
-                        target.health
+                        "   ⇒ {} {}now has {} health{}",
+                        name,
+                        damage,
+                        target.health,
+                        describe_effects(target)
                     );
                 }
             }
+
+            if !action.cost.is_free() {
+                println!(
+                    "   ⇒ {} {}",
+                    source,
+                    describe_spending(previous.targeted_member(&action.source), &action.cost)
+                );
+            }
+        }
+    }
+
+    // Status effects take their toll at the end of every turn.
+    for party in &event.state.parties {
+        for member in &party.members {
+            let participant = Participant {
+                party_id: party.id,
+                member_id: member.id,
+            };
+            let before = previous.targeted_member(&participant);
+            if event.action.targets().contains(&participant) || before.health == member.health {
+                continue;
+            }
+
+            let name = color_participant(initiator_side, names, &participant);
+            if member.is_dead() {
+                println!("   ⇒ {} has {}", name, "succumbed to poison".red());
+            } else if member.health < before.health {
+                println!(
+                    "   ⇒ {} suffers from poison and now has {} health",
+                    name, member.health
+                );
+            } else {
+                println!(
+                    "   ⇒ {} regenerates and now has {} health",
+                    name, member.health
+                );
+            }
         }
     }
 }
 
+/// Describes the effect of a spell on its target, given the target before the cast.
+fn describe_spell_effect(target: &PartyMember, effect: &Action) -> String {
+    match effect {
+        Action::SimpleAttack(attack) => match attack.roll {
+            AttackRoll::Hit => format!(", dealing {}", describe_damage(target, attack)),
+            AttackRoll::Critical => format!(
+                ", {} {}",
+                "critically dealing".bright_red(),
+                describe_damage(target, attack)
+            ),
+            AttackRoll::Miss => ", but it misses".to_string(),
+        },
+        Action::Heal(heal) => format!(", restoring up to {} health", heal.amount),
+        Action::Buff(buff) => format!(", adding {} damage to their attacks", buff.damage_bonus),
+        Action::StatusEffect(effect) => format!(", leaving them {}", effect.to_string().green()),
+        Action::Spell(spell) => describe_spell_effect(target, &spell.effect),
+        Action::Defend(_) | Action::Guard => String::default(),
+    }
+}
+
+/// Describes the damage an attack deals to a member, along with the raw
+/// damage if armor or resistances changed it.
+fn describe_damage(target: &PartyMember, attack: &SimpleAttackAction) -> String {
+    let damage = target.mitigate(attack);
+    if damage == attack.damage {
+        format!("{} damage", damage)
+    } else {
+        format!(
+            "{} damage ({} before {})",
+            damage,
+            attack.damage,
+            match target.stance {
+                Some(Stance::Defending { .. }) => "armor, resistances and defending",
+                _ => "armor and resistances",
+            }
+            .cyan()
+        )
+    }
+}
+
+/// Lists the status effects affecting a member, if any.
+fn describe_effects(member: &PartyMember) -> String {
+    if member.effects.is_empty() {
+        return String::default();
+    }
+
+    let effects: Vec<_> = member
+        .effects
+        .iter()
+        .map(|effect| effect.to_string().green().to_string())
+        .collect();
+    format!(" and is {}", effects.join(" and "))
+}
+
+/// Describes the armor and resistances of a member, if any.
+fn describe_defenses(member: &PartyMember) -> String {
+    let mut defenses = Vec::default();
+    if member.armor > 0.0 {
+        defenses.push(format!("wearing {} armor", member.armor));
+    }
+
+    let modifiers: Vec<_> = member
+        .resistances
+        .iter()
+        .map(|r| format!("{}% of {:?}", r.multiplier * 100.0, r.damage_type).to_lowercase())
+        .collect();
+    if !modifiers.is_empty() {
+        defenses.push(format!("taking {} damage", modifiers.join(" and ")));
+    }
+
+    if defenses.is_empty() {
+        String::default()
+    } else {
+        format!(", {}", defenses.join(", ").cyan())
+    }
+}
+
+/// Names the row a member stands in, if the members of the conflict line up in formations.
+fn describe_row(conflict: &Conflict, member: &PartyMember) -> String {
+    if conflict.formations {
+        format!(" ({} row)", row_name(member.row))
+    } else {
+        String::default()
+    }
+}
+
+/// Names a row of a formation.
+fn row_name(row: Row) -> &'static str {
+    match row {
+        Row::Front => "front",
+        Row::Back => "back",
+    }
+}
+
+/// Lists the abilities of a member, if any.
+fn describe_abilities(member: &PartyMember) -> String {
+    if member.abilities.is_empty() {
+        return String::default();
+    }
+
+    let abilities: Vec<_> = member
+        .abilities
+        .iter()
+        .map(|ability| {
+            let cost = if ability.cost.is_free() {
+                String::default()
+            } else {
+                format!(" for {}", ability.cost)
+            };
+            format!("{:?}{}", ability.action, cost).green().to_string()
+        })
+        .collect();
+    format!(", able to {}", abilities.join(" or "))
+}
+
+/// Lists the spells of a member, if any.
+fn describe_spells(member: &PartyMember) -> String {
+    if member.spells.is_empty() {
+        return String::default();
+    }
+
+    let spells: Vec<_> = member
+        .spells
+        .iter()
+        .map(|spell| {
+            let mut details = vec![match &spell.action {
+                Action::SimpleAttack(attack) => match &attack.weapon {
+                    Some(weapon) => {
+                        format!("{} {:?} damage", attack.damage, weapon.damage_type).to_lowercase()
+                    }
+                    None => format!("{} damage", attack.damage),
+                },
+                action => format!("{:?}", action),
+            }];
+            match spell.area {
+                WeaponArea::Single => {}
+                WeaponArea::Cleave => details.push("cleaving".to_string()),
+                WeaponArea::All => details.push("affecting everyone".to_string()),
+            }
+            if !spell.cost.is_free() {
+                details.push(format!("costs {}", spell.cost));
+            }
+            if spell.cooldown > 0 {
+                details.push(format!("{} turn cooldown", spell.cooldown));
+            }
+            format!("{} ({})", spell.name, details.join(", "))
+                .magenta()
+                .to_string()
+        })
+        .collect();
+    format!(", casting {}", spells.join(" or "))
+}
+
+/// Lists the resources of a member, if any, along with their regeneration.
+fn describe_resources(member: &PartyMember) -> String {
+    let resources: Vec<_> = [("mana", &member.mana), ("stamina", &member.stamina)]
+        .into_iter()
+        .filter(|(_, pool)| pool.max > 0.0)
+        .map(|(name, pool)| {
+            if pool.regen > 0.0 {
+                format!("{} {} (+{} per turn)", pool.current, name, pool.regen)
+            } else {
+                format!("{} {}", pool.current, name)
+            }
+        })
+        .collect();
+
+    resources
+        .iter()
+        .map(|resource| format!(", {}", resource.bright_blue()))
+        .collect()
+}
+
+/// Describes the resources a member spent on an action and what is left of them,
+/// given the member before taking the action.
+fn describe_spending(member: &PartyMember, cost: &Cost) -> String {
+    let remaining: Vec<_> = [
+        ("mana", cost.mana, &member.mana),
+        ("stamina", cost.stamina, &member.stamina),
+    ]
+    .into_iter()
+    .filter(|(_, spent, _)| *spent > 0.0)
+    .map(|(name, spent, pool)| format!("{} {}", pool.current - spent, name))
+    .collect();
+    format!(
+        "spends {}, leaving {}",
+        cost.to_string().bright_blue(),
+        remaining.join(" and ")
+    )
+}
+
+/// Parses a non-negative number of seconds.
+fn parse_seconds(text: &str) -> Result<Duration, String> {
+    let seconds: f64 = text.parse().map_err(|e| format!("{e}"))?;
+    Duration::try_from_secs_f64(seconds).map_err(|e| format!("{e}"))
+}
+
+/// Fills in random names for all members that were not named explicitly.
+fn complete_names(names: &[Option<String>], language: &Language) -> Vec<String> {
+    let rng = RNG::from(language);
+    let generated = rng.generate_names(names.len(), false);
+    names
+        .iter()
+        .zip(generated)
+        .map(|(name, generated)| name.clone().unwrap_or(generated))
+        .collect()
+}
+
+/// Names a party by its side in the conflict, e.g. "the attacking side".
+fn describe_party(conflict: &Conflict, party_id: usize) -> String {
+    match conflict
+        .parties
+        .iter()
+        .position(|party| party.id == party_id)
+    {
+        Some(0) => "the attacking side".to_string(),
+        Some(1) => "the defending side".to_string(),
+        _ => format!("party {party_id}"),
+    }
+}
+
 fn color_participant(
-    initiator_party: usize,
-    names: &Vec<Vec<String>>,
+    initiator_side: &[usize],
+    names: &[Vec<String>],
     target: &Participant,
 ) -> ColoredString {
-    if target.party_id == initiator_party {
+    if initiator_side.contains(&target.party_id) {
         names[target.party_id][target.member_id].blue()
     } else {
         names[target.party_id][target.member_id].purple()
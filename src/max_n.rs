@@ -0,0 +1,426 @@
+use crate::action::AppliedAction;
+use crate::conflict::Conflict;
+use crate::party::Party;
+use crate::solver::{Event, Outcome, OutcomeType, PartyResult, DEADLINE_CHECK_INTERVAL};
+use crate::utility_value::Evaluator;
+use crate::value::TerminalState;
+use log::trace;
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// The max^n search for conflicts in which parties follow preferences of their own.
+///
+/// Where minimax assumes every opponent plays against the initiating party, max^n
+/// evaluates each state for every party and lets the acting party pick the action
+/// that is best in its own eyes, e.g. a cowardly party retreating from a fight it
+/// could win at great cost. Since the game is no longer zero-sum, no branch can be
+/// pruned; the search is exhaustive up to its depth limit.
+pub struct MaxN<'a> {
+    /// Scores the states of the conflict for the initiating party, unless it has a personality.
+    evaluator: &'a dyn Evaluator,
+    /// The values of states already searched, by the remaining search depth.
+    table: HashMap<(Conflict, usize), Value>,
+    /// The point in time at which to abort the search, if any.
+    deadline: Option<Instant>,
+    /// The number of node evaluations performed.
+    evaluations: usize,
+    /// The number of states resolved from the table.
+    transposition_hits: usize,
+    /// The depth of the deepest node evaluated.
+    max_visited_depth: usize,
+}
+
+/// The value of a state, as seen by every party.
+#[derive(Debug, Clone)]
+struct Value {
+    /// The utility of the state for every party, in the order of [`Conflict::parties`].
+    utilities: Vec<f32>,
+    /// The value of the state in the eyes of the initiating party.
+    value: TerminalState,
+    /// The probability of the initiating party winning.
+    win_probability: f32,
+    /// The action the acting party prefers, unless the state is terminal.
+    best_action: Option<AppliedAction>,
+    /// `true` if the search stopped at the depth limit below this state.
+    depth_limited: bool,
+}
+
+impl<'a> MaxN<'a> {
+    /// Runs depth-limited searches of increasing depth until the conflict
+    /// is resolved, the maximum depth is reached or time runs out.
+    ///
+    /// ## Arguments
+    /// * `conflict` - The conflict situation to resolve.
+    /// * `min_depth` - The search depth of the first iteration.
+    /// * `max_depth` - The maximum search depth.
+    /// * `deadline` - The point in time at which to abort the search, if any.
+    /// * `evaluator` - Scores the states of the conflict in the eyes of the initiating party,
+    ///   unless it has a personality of its own.
+    ///
+    /// ## Returns
+    /// The [`Outcome`] of the deepest complete iteration.
+    pub fn search(
+        conflict: &Conflict,
+        min_depth: usize,
+        max_depth: usize,
+        deadline: Option<Instant>,
+        evaluator: &'a dyn Evaluator,
+    ) -> Outcome {
+        let start_time = Instant::now();
+        let mut search = Self {
+            evaluator,
+            table: HashMap::default(),
+            deadline: None,
+            evaluations: 0,
+            transposition_hits: 0,
+            max_visited_depth: 0,
+        };
+
+        let mut depth = min_depth.clamp(1, max_depth.max(1));
+        let mut best: Option<Outcome> = None;
+        loop {
+            trace!("Searching all parties' preferences to depth {depth}");
+
+            // The first iteration always completes so that there is a result to return.
+            search.deadline = deadline.filter(|_| best.is_some());
+            let outcome = match search.evaluate(conflict, None, 0, depth) {
+                Some(value) => search.outcome(conflict, &value, depth, start_time),
+                None => {
+                    trace!("Search at depth {depth} timed out");
+                    return best.expect("The first iteration always completes");
+                }
+            };
+
+            if !outcome.depth_limited || depth >= max_depth {
+                return outcome;
+            }
+
+            depth += 1;
+            best = Some(outcome);
+        }
+    }
+
+    /// Determines the value of a state by letting the acting party choose the action
+    /// it prefers, and weighing the results of random actions by their probability.
+    ///
+    /// ## Arguments
+    /// * `state` - The state to evaluate.
+    /// * `action` - The action that led to the state, if any.
+    /// * `depth` - The depth of the state.
+    /// * `max_depth` - The depth limit of the search.
+    ///
+    /// ## Returns
+    /// The value of the state, or [`None`] if the search was aborted.
+    fn evaluate(
+        &mut self,
+        state: &Conflict,
+        action: Option<&AppliedAction>,
+        depth: usize,
+        max_depth: usize,
+    ) -> Option<Value> {
+        self.evaluations += 1;
+        self.max_visited_depth = self.max_visited_depth.max(depth);
+
+        // Reading the clock is comparatively expensive, so it is only done occasionally.
+        if self.evaluations % DEADLINE_CHECK_INTERVAL == 0
+            && self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return None;
+        }
+
+        // A party that retreated from the encounter grants the other party
+        // one last action, after which this is a terminal state. The search
+        // always starts with an action, since its start may follow a retreat.
+        if action.is_some() && state.has_ended_after(action) {
+            return Some(self.leaf(state, false));
+        }
+
+        let key = (state.clone(), max_depth - depth);
+        if let Some(value) = self.table.get(&key) {
+            self.transposition_hits += 1;
+            return Some(value.clone());
+        }
+
+        if depth == max_depth {
+            return Some(self.leaf(state, true));
+        }
+
+        let party_id = state.acting_party().id;
+        let party_index = state
+            .parties
+            .iter()
+            .position(|party| party.id == party_id)
+            .expect("The party is involved in the conflict");
+
+        let mut best: Option<Value> = None;
+        let mut depth_limited = false;
+        for action in state.actions().into_iter().flatten() {
+            if state.apply(party_id, &action).is_none() {
+                continue;
+            }
+
+            let value = self.evaluate_outcomes(state, party_id, &action, depth, max_depth)?;
+            depth_limited |= value.depth_limited;
+
+            // On equal utility, the first action wins.
+            let is_better = match &best {
+                Some(best) => value.utilities[party_index] > best.utilities[party_index],
+                None => true,
+            };
+            if is_better {
+                best = Some(Value {
+                    best_action: Some(action),
+                    ..value
+                });
+            }
+        }
+
+        // A party without any applicable action ends the conflict.
+        let value = match best {
+            Some(best) => Value {
+                depth_limited,
+                ..best
+            },
+            None => self.leaf(state, false),
+        };
+        self.table.insert(key, value.clone());
+        Some(value)
+    }
+
+    /// Determines the expected value of an action over all of its random results.
+    ///
+    /// ## Returns
+    /// The expected value, or [`None`] if the search was aborted.
+    fn evaluate_outcomes(
+        &mut self,
+        state: &Conflict,
+        party_id: usize,
+        action: &AppliedAction,
+        depth: usize,
+        max_depth: usize,
+    ) -> Option<Value> {
+        let mut expected = Value {
+            utilities: vec![0.0; state.parties.len()],
+            value: TerminalState::Expected(0.0),
+            win_probability: 0.0,
+            best_action: None,
+            depth_limited: false,
+        };
+
+        let outcomes = action.outcomes();
+        let is_random = outcomes.len() > 1;
+        let mut applied = 0.0;
+        for (probability, outcome) in outcomes {
+            let Some(child) = state.apply(party_id, &outcome) else {
+                continue;
+            };
+
+            let value = self.evaluate(&child, Some(&outcome), depth + 1, max_depth)?;
+            if !is_random {
+                return Some(value);
+            }
+
+            for (expected, utility) in expected.utilities.iter_mut().zip(&value.utilities) {
+                *expected += probability * utility;
+            }
+            expected.value =
+                TerminalState::Expected(expected.value.value() + probability * value.value.value());
+            expected.win_probability += probability * value.win_probability;
+            expected.depth_limited |= value.depth_limited;
+            applied += probability;
+        }
+
+        // Results that cannot be applied do not happen; the others share their probability.
+        if applied > 0.0 {
+            for utility in expected.utilities.iter_mut() {
+                *utility /= applied;
+            }
+            expected.value = TerminalState::Expected(expected.value.value() / applied);
+            expected.win_probability /= applied;
+        }
+
+        Some(expected)
+    }
+
+    /// Evaluates a state in which the search ends for every party. Parties without
+    /// a personality of their own side with or against the initiating party, just
+    /// like in the zero-sum search.
+    fn leaf(&self, state: &Conflict, depth_limited: bool) -> Value {
+        let value = Self::initiator_utility(state, self.evaluator);
+        let utilities = state
+            .parties
+            .iter()
+            .map(|party| Self::party_utility(state, party, value))
+            .collect();
+        Value {
+            utilities,
+            win_probability: value.win_probability(),
+            value,
+            best_action: None,
+            depth_limited,
+        }
+    }
+
+    /// Determines the utility of a state for a party, in its own eyes.
+    ///
+    /// ## Arguments
+    /// * `state` - The state to evaluate.
+    /// * `party_id` - The ID of the party.
+    /// * `evaluator` - Scores the state for the initiating party, unless it has a personality.
+    pub fn utility(state: &Conflict, party_id: usize, evaluator: &dyn Evaluator) -> f32 {
+        let value = Self::initiator_utility(state, evaluator);
+        Self::party_utility(state, state.action_target(party_id), value)
+    }
+
+    /// Evaluates a state for the initiating party.
+    fn initiator_utility(state: &Conflict, evaluator: &dyn Evaluator) -> TerminalState {
+        let initiator = state.initiator();
+        match &initiator.personality {
+            Some(personality) => personality.utility(state, initiator.id),
+            None => evaluator.utility(state, initiator.id),
+        }
+    }
+
+    /// Evaluates a state for a party, given its value for the initiating party.
+    fn party_utility(state: &Conflict, party: &Party, value: TerminalState) -> f32 {
+        match &party.personality {
+            Some(personality) => personality.utility(state, party.id).value(),
+            None if party.is_allied_with(state.initiator()) => value.value(),
+            None => -value.value(),
+        }
+    }
+
+    /// Follows the preferred actions from the start, continuing with the
+    /// most likely result of random actions.
+    fn outcome(
+        &self,
+        conflict: &Conflict,
+        value: &Value,
+        max_depth: usize,
+        start_time: Instant,
+    ) -> Outcome {
+        let mut timeline = Vec::default();
+        let mut state = conflict.clone();
+        for depth in 1..=max_depth {
+            let action = match self
+                .table
+                .get(&(state.clone(), max_depth - depth + 1))
+                .and_then(|entry| entry.best_action.clone())
+            {
+                Some(action) => action,
+                None => break,
+            };
+
+            let is_initiator_turn = state.is_initiator_turn();
+            let party_id = state.acting_party().id;
+            let (probability, action) = action.most_likely_outcome();
+            state = match state.apply(party_id, &action) {
+                Some(state) => state,
+                None => break,
+            };
+
+            timeline.push(Event {
+                turn: depth,
+                party_id,
+                is_initiator_turn,
+                action: action.clone(),
+                probability,
+                depth,
+                state: state.clone(),
+            });
+
+            if state.has_ended_after(Some(&action)) {
+                break;
+            }
+        }
+
+        let results = PartyResult::from_state(&state);
+        Outcome {
+            outcome: OutcomeType::from(value.value),
+            results,
+            timeline,
+            evaluations: self.evaluations,
+            cuts: 0,
+            transposition_hits: self.transposition_hits,
+            win_probability: value.win_probability,
+            search_duration: Instant::now() - start_time,
+            max_visited_depth: self.max_visited_depth,
+            search_depth: max_depth,
+            depth_limited: value.depth_limited,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::{Action, AreaAction, AttackRoll, Cost, SimpleAttackAction};
+    use crate::conflict::TurnOrder;
+    use crate::party::Participant;
+    use crate::party_member::PartyMember;
+    use crate::utility_value::Personality;
+    use crate::weapon::Weapon;
+
+    #[test]
+    fn rejected_results_share_their_probability_with_the_others() {
+        let weapon = Weapon {
+            name: "a club".to_string(),
+            damage: 10.0,
+            hit_chance: 0.5,
+            ..Default::default()
+        };
+        let mut villains = Party::new(1, vec![PartyMember::new(0, 5.0, weapon.clone())]);
+        villains.personality = Some(Personality::cautious());
+        let conflict = Conflict {
+            parties: vec![
+                Party::new(0, vec![PartyMember::new(0, 30.0, weapon.clone())]),
+                villains,
+            ],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
+        };
+
+        // The villain cannot be struck a second time once the first blow downed them,
+        // so only the miss can be applied.
+        let villain = Participant {
+            party_id: 1,
+            member_id: 0,
+        };
+        let action = AppliedAction::Area(AreaAction {
+            action: Action::SimpleAttack(SimpleAttackAction {
+                weapon: Some(weapon),
+                damage: 10.0,
+                roll: AttackRoll::Hit,
+            }),
+            source: Participant {
+                party_id: 0,
+                member_id: 0,
+            },
+            targets: vec![villain.clone(), villain],
+            cost: Cost::default(),
+        });
+        let outcomes = action.outcomes();
+        assert_eq!(outcomes.len(), 2);
+        assert!(conflict.apply(0, &outcomes[0].1).is_none());
+        let miss = conflict.apply(0, &outcomes[1].1).unwrap();
+
+        let evaluator = Personality::default();
+        let mut search = MaxN {
+            evaluator: &evaluator,
+            table: HashMap::default(),
+            deadline: None,
+            evaluations: 0,
+            transposition_hits: 0,
+            max_visited_depth: 0,
+        };
+        let expected = search
+            .evaluate_outcomes(&conflict, 0, &action, 0, 2)
+            .unwrap();
+        let missed = search.evaluate(&miss, Some(&outcomes[1].1), 1, 2).unwrap();
+        assert_eq!(expected.utilities, missed.utilities);
+        assert_eq!(expected.value.value(), missed.value.value());
+        assert_eq!(expected.win_probability, missed.win_probability);
+    }
+}
@@ -0,0 +1,632 @@
+use crate::action::AppliedAction;
+use crate::conflict::Conflict;
+use crate::party::Party;
+use crate::solver::{Event, Outcome, OutcomeType, PartyResult};
+use crate::utility_value::Evaluator;
+use crate::value::TerminalState;
+use log::trace;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::time::Instant;
+
+/// The maximum number of random actions in a playout. Playouts that do not
+/// end the conflict by then are evaluated heuristically.
+const MAX_PLAYOUT_LENGTH: usize = 1000;
+
+/// The seed of the random number generator, making searches reproducible.
+const SEED: u64 = 0x5eed;
+
+/// Monte Carlo Tree Search using the UCT selection policy.
+///
+/// Where the minimax solver explores every action up to a fixed depth,
+/// the tree search grows the tree towards promising actions and estimates
+/// the value of new states by playing the conflict out with random actions.
+pub struct MonteCarlo<'a> {
+    /// Scores the states of the conflict in the eyes of the initiating party.
+    evaluator: &'a dyn Evaluator,
+    /// All nodes of the search tree; the root node has ID zero.
+    nodes: Vec<Node>,
+    /// The source of randomness for action selection, playouts and rolls.
+    rng: StdRng,
+    /// The exploration constant of UCT.
+    exploration: f32,
+    /// The lowest utility observed, used to normalize values.
+    min_value: f32,
+    /// The highest utility observed, used to normalize values.
+    max_value: f32,
+    /// Whether any action with random results was encountered.
+    is_stochastic: bool,
+}
+
+/// A node in the search tree.
+#[derive(Debug, Clone)]
+struct Node {
+    /// The ID of the node's immediate parent.
+    /// Is [`None`] only for the root node.
+    parent_id: Option<usize>,
+    /// The action taken to arrive at this node.
+    /// Is [`None`] only for the root node.
+    action: Option<AppliedAction>,
+    /// The state after applying the action. Chance nodes hold the state
+    /// before their action is applied.
+    state: Conflict,
+    /// Whether the initiating party acts next.
+    is_maximizing: bool,
+    /// The depth of the node. Chance nodes share the depth of their parent.
+    depth: usize,
+    /// The probability of this node's action having its result.
+    probability: f32,
+    /// The kind of node, along with its children.
+    kind: NodeKind,
+    /// The number of playouts through this node.
+    visits: u32,
+    /// The sum of the utilities of all playouts through this node.
+    total_value: f32,
+    /// The number of playouts through this node won by the initiating party.
+    wins: u32,
+}
+
+/// The next step when descending the tree.
+enum Step {
+    /// Expand a decision node with an untried action.
+    Expand(AppliedAction),
+    /// Select among the children of a fully expanded decision node.
+    Select(Vec<usize>),
+    /// Descend into an existing child.
+    Descend(usize),
+    /// Add the rolled result of a chance node, given by its index, probability and action.
+    Roll(usize, (f32, AppliedAction)),
+}
+
+/// The kind of node in the search tree.
+#[derive(Debug, Clone)]
+enum NodeKind {
+    /// A party decides on an action.
+    Decision {
+        /// The applicable actions that were not expanded yet.
+        untried: Vec<AppliedAction>,
+        /// The IDs of the expanded children.
+        children: Vec<usize>,
+    },
+    /// The result of an action is rolled.
+    Chance {
+        /// The possible results of the action and their probabilities.
+        outcomes: Vec<(f32, AppliedAction)>,
+        /// The IDs of the expanded children, indexed like `outcomes`.
+        children: Vec<Option<usize>>,
+    },
+    /// The conflict has ended.
+    Terminal(TerminalState),
+}
+
+impl<'a> MonteCarlo<'a> {
+    /// Runs the tree search to find the most promising sequence of actions
+    /// to resolve the conflict, in favor of the initiating party.
+    ///
+    /// ## Arguments
+    /// * `conflict` - The conflict situation to resolve.
+    /// * `iterations` - The number of playouts to perform.
+    /// * `exploration` - The exploration constant of UCT; higher values
+    ///   spread the playouts more evenly across actions.
+    /// * `evaluator` - Scores the states of the conflict.
+    ///
+    /// ## Returns
+    /// The [`Outcome`] of the conflict.
+    pub fn search(
+        conflict: &Conflict,
+        iterations: usize,
+        exploration: f32,
+        evaluator: &'a dyn Evaluator,
+    ) -> Outcome {
+        let start_time = Instant::now();
+        let mut search = Self {
+            evaluator,
+            nodes: Vec::default(),
+            rng: StdRng::seed_from_u64(SEED),
+            exploration,
+            min_value: f32::INFINITY,
+            max_value: f32::NEG_INFINITY,
+            is_stochastic: false,
+        };
+
+        let is_maximizing = conflict.is_initiator_turn();
+        search.add_decision_node(None, None, conflict.clone(), is_maximizing, 0, 1.0);
+
+        for _ in 0..iterations {
+            let leaf = search.select_and_expand();
+            let value = search.simulate(leaf);
+            search.backpropagate(leaf, value);
+        }
+
+        search.into_outcome(iterations, start_time)
+    }
+
+    /// Descends the tree along the most promising actions and expands
+    /// the first node that has unexplored actions.
+    ///
+    /// ## Returns
+    /// The ID of the node to simulate from.
+    fn select_and_expand(&mut self) -> usize {
+        let mut id = 0;
+        loop {
+            let step = match &mut self.nodes[id].kind {
+                NodeKind::Terminal(_) => return id,
+                NodeKind::Decision { untried, children } => match untried.pop() {
+                    Some(action) => Step::Expand(action),
+                    None => Step::Select(children.clone()),
+                },
+                NodeKind::Chance { outcomes, children } => {
+                    let index = Self::roll(&mut self.rng, outcomes);
+                    match children[index] {
+                        Some(child_id) => Step::Descend(child_id),
+                        None => Step::Roll(index, outcomes[index].clone()),
+                    }
+                }
+            };
+
+            match step {
+                Step::Expand(action) => {
+                    let child_id = self.expand(id, action);
+                    if let NodeKind::Terminal(_) | NodeKind::Decision { .. } =
+                        self.nodes[child_id].kind
+                    {
+                        return child_id;
+                    }
+
+                    // A new chance node immediately rolls its first result.
+                    id = child_id;
+                }
+                Step::Select(children) => id = self.select_child(id, &children),
+                Step::Descend(child_id) => id = child_id,
+                Step::Roll(index, (probability, action)) => {
+                    let child_id = self.apply(id, action, probability);
+                    if let NodeKind::Chance { children, .. } = &mut self.nodes[id].kind {
+                        children[index] = Some(child_id);
+                    }
+
+                    return child_id;
+                }
+            }
+        }
+    }
+
+    /// Selects the child of a decision node with the highest upper confidence bound.
+    fn select_child(&self, parent_id: usize, children: &[usize]) -> usize {
+        let parent = &self.nodes[parent_id];
+        let log_visits = (parent.visits.max(1) as f32).ln();
+
+        let score = |id: &usize| {
+            let child = &self.nodes[*id];
+            let value = self.normalize(child.mean_value());
+            let value = if parent.is_maximizing {
+                value
+            } else {
+                1.0 - value
+            };
+            value + self.exploration * (log_visits / child.visits.max(1) as f32).sqrt()
+        };
+
+        *children
+            .iter()
+            .max_by(|a, b| score(a).total_cmp(&score(b)))
+            .expect("A fully expanded decision node has children")
+    }
+
+    /// Expands a decision node with an action. Actions with random
+    /// results are expanded into a chance node.
+    ///
+    /// ## Returns
+    /// The ID of the new child node.
+    fn expand(&mut self, parent_id: usize, action: AppliedAction) -> usize {
+        let outcomes = action.outcomes();
+        if outcomes.len() == 1 {
+            return self.apply(parent_id, action, 1.0);
+        }
+
+        self.is_stochastic = true;
+        let parent = &self.nodes[parent_id];
+        let node = Node {
+            parent_id: Some(parent_id),
+            action: Some(action),
+            state: parent.state.clone(),
+            is_maximizing: parent.is_maximizing,
+            depth: parent.depth,
+            probability: 1.0,
+            kind: NodeKind::Chance {
+                children: vec![None; outcomes.len()],
+                outcomes,
+            },
+            visits: 0,
+            total_value: 0.0,
+            wins: 0,
+        };
+
+        self.push_child(parent_id, node)
+    }
+
+    /// Applies an action, or a rolled result of an action, to the state of a node.
+    ///
+    /// ## Returns
+    /// The ID of the new child node.
+    fn apply(&mut self, parent_id: usize, action: AppliedAction, probability: f32) -> usize {
+        let parent = &self.nodes[parent_id];
+        let party_id = parent.state.acting_party().id;
+        let state = parent
+            .state
+            .apply(party_id, &action)
+            .expect("Only applicable actions are expanded");
+        let is_maximizing = state.is_initiator_turn();
+        let depth = parent.depth + 1;
+
+        self.add_decision_node(
+            Some(parent_id),
+            Some(action),
+            state,
+            is_maximizing,
+            depth,
+            probability,
+        )
+    }
+
+    /// Adds a node in which a party decides on an action,
+    /// or a terminal node if the conflict has ended.
+    ///
+    /// ## Returns
+    /// The ID of the new node.
+    fn add_decision_node(
+        &mut self,
+        parent_id: Option<usize>,
+        action: Option<AppliedAction>,
+        state: Conflict,
+        is_maximizing: bool,
+        depth: usize,
+        probability: f32,
+    ) -> usize {
+        let kind = if Self::has_ended(&state, action.as_ref()) {
+            NodeKind::Terminal(self.evaluator.utility(&state, state.initiator().id))
+        } else {
+            let mut untried = Self::applicable_actions(&state);
+            untried.shuffle(&mut self.rng);
+            if untried.is_empty() {
+                NodeKind::Terminal(self.evaluator.utility(&state, state.initiator().id))
+            } else {
+                NodeKind::Decision {
+                    untried,
+                    children: Vec::default(),
+                }
+            }
+        };
+
+        let node = Node {
+            parent_id,
+            action,
+            state,
+            is_maximizing,
+            depth,
+            probability,
+            kind,
+            visits: 0,
+            total_value: 0.0,
+            wins: 0,
+        };
+
+        match parent_id {
+            Some(parent_id) => self.push_child(parent_id, node),
+            None => {
+                self.nodes.push(node);
+                0
+            }
+        }
+    }
+
+    /// Adds a node to the tree and registers it with its decision node parent.
+    /// Children of chance nodes are registered by the caller.
+    fn push_child(&mut self, parent_id: usize, node: Node) -> usize {
+        let id = self.nodes.len();
+        if let Some(action) = &node.action {
+            trace!("Expand node {parent_id} into {id} with action: {action}");
+        }
+
+        self.nodes.push(node);
+        if let NodeKind::Decision { children, .. } = &mut self.nodes[parent_id].kind {
+            children.push(id);
+        }
+
+        id
+    }
+
+    /// Estimates the utility of a node by playing the conflict out with random actions.
+    fn simulate(&mut self, id: usize) -> TerminalState {
+        let node = &self.nodes[id];
+        if let NodeKind::Terminal(value) = node.kind {
+            return value;
+        }
+
+        let mut state = node.state.clone();
+        let mut last_action = node.action.clone();
+
+        for _ in 0..MAX_PLAYOUT_LENGTH {
+            if Self::has_ended(&state, last_action.as_ref()) {
+                break;
+            }
+
+            let mut actions = match state.actions() {
+                Some(actions) => actions.collect::<Vec<_>>(),
+                None => break,
+            };
+
+            // Pick random actions until one can be applied.
+            let party_id = state.acting_party().id;
+            let mut next = None;
+            while !actions.is_empty() {
+                let action = actions.swap_remove(self.rng.gen_range(0..actions.len()));
+                let outcomes = action.outcomes();
+                self.is_stochastic |= outcomes.len() > 1;
+                let (_, action) = outcomes[Self::roll(&mut self.rng, &outcomes)].clone();
+                if let Some(next_state) = state.apply(party_id, &action) {
+                    next = Some((action, next_state));
+                    break;
+                }
+            }
+
+            match next {
+                Some((action, next_state)) => {
+                    state = next_state;
+                    last_action = Some(action);
+                }
+                None => break,
+            }
+        }
+
+        self.evaluator.utility(&state, state.initiator().id)
+    }
+
+    /// Updates the statistics of a node and all of its ancestors with the result of a playout.
+    fn backpropagate(&mut self, id: usize, value: TerminalState) {
+        let is_win = matches!(value, TerminalState::Win(_));
+        let value = value.value();
+        self.min_value = self.min_value.min(value);
+        self.max_value = self.max_value.max(value);
+
+        let mut current = Some(id);
+        while let Some(id) = current {
+            let node = &mut self.nodes[id];
+            node.visits += 1;
+            node.total_value += value;
+            node.wins += is_win as u32;
+            current = node.parent_id;
+        }
+    }
+
+    /// Builds the outcome by following the most visited children from the root.
+    fn into_outcome(self, iterations: usize, start_time: Instant) -> Outcome {
+        let mut timeline = Vec::default();
+        let mut last = 0;
+        while let Some(id) = self.most_visited_child(last) {
+            let node = &self.nodes[id];
+            // Chance nodes only select among the results of their action.
+            if let (NodeKind::Decision { .. } | NodeKind::Terminal(_), Some(action)) =
+                (&node.kind, &node.action)
+            {
+                // The node's side is the one to move next; the acting side is the parent's.
+                let parent = &self.nodes[node.parent_id.unwrap_or_default()];
+                timeline.push(Event {
+                    turn: node.depth,
+                    party_id: parent.state.acting_party().id,
+                    is_initiator_turn: parent.is_maximizing,
+                    action: action.clone(),
+                    probability: node.probability,
+                    depth: node.depth,
+                    state: node.state.clone(),
+                });
+            }
+
+            last = id;
+        }
+
+        // The line ends in a terminal state unless the tree did not grow that far.
+        let terminal = match self.nodes[last].kind {
+            NodeKind::Terminal(value) => Some(value),
+            _ => None,
+        };
+
+        // The estimates are those of the most promising action.
+        let node = self
+            .most_visited_child(0)
+            .map_or(&self.nodes[0], |id| &self.nodes[id]);
+        let mean_value = node.mean_value();
+        let win_probability = node.wins as f32 / node.visits.max(1) as f32;
+
+        let outcome = match terminal {
+            _ if self.is_stochastic => OutcomeType::Expected(mean_value),
+            Some(value) => OutcomeType::from(value),
+            None => OutcomeType::Unknown(mean_value),
+        };
+
+        // The tree search has no depth limit; it grows as deep as the playouts lead it.
+        let max_visited_depth = self.nodes.iter().map(|n| n.depth).max().unwrap_or(0);
+        let last_state = timeline.last().map_or(&self.nodes[0].state, |e| &e.state);
+        let results = PartyResult::from_state(last_state);
+        Outcome {
+            outcome,
+            results,
+            timeline,
+            evaluations: iterations,
+            cuts: 0,
+            transposition_hits: 0,
+            win_probability,
+            search_duration: Instant::now() - start_time,
+            max_visited_depth,
+            search_depth: max_visited_depth,
+            depth_limited: terminal.is_none(),
+        }
+    }
+
+    /// Selects the most visited child of a node, if it has any.
+    fn most_visited_child(&self, id: usize) -> Option<usize> {
+        let children = match &self.nodes[id].kind {
+            NodeKind::Decision { children, .. } => children.clone(),
+            NodeKind::Chance { children, .. } => children.iter().flatten().copied().collect(),
+            NodeKind::Terminal(_) => return None,
+        };
+
+        // On equal visits, the first expanded child wins.
+        children
+            .into_iter()
+            .rev()
+            .max_by_key(|child| self.nodes[*child].visits)
+    }
+
+    /// Normalizes a utility to the range of utilities observed so far.
+    fn normalize(&self, value: f32) -> f32 {
+        let range = self.max_value - self.min_value;
+        if range > 0.0 {
+            (value - self.min_value) / range
+        } else {
+            0.5
+        }
+    }
+
+    /// Determines whether the conflict ended with the action that led to a state.
+    /// The search always starts with an action, since its start may follow a retreat.
+    fn has_ended(state: &Conflict, action: Option<&AppliedAction>) -> bool {
+        state.initiator().is_defeated()
+            || state.hostile_parties().all(Party::is_defeated)
+            || (action.is_some() && state.has_ended_after(action))
+    }
+
+    /// Lists all actions of the acting party that can be applied to a state.
+    fn applicable_actions(state: &Conflict) -> Vec<AppliedAction> {
+        let party_id = state.acting_party().id;
+        match state.actions() {
+            Some(actions) => actions
+                .filter(|action| state.apply(party_id, action).is_some())
+                .collect(),
+            None => Vec::default(),
+        }
+    }
+
+    /// Rolls one of the results of an action.
+    ///
+    /// ## Returns
+    /// The index of the rolled result.
+    fn roll(rng: &mut StdRng, outcomes: &[(f32, AppliedAction)]) -> usize {
+        let mut roll = rng.gen::<f32>();
+        for (index, (probability, _)) in outcomes.iter().enumerate() {
+            if roll < *probability {
+                return index;
+            }
+            roll -= probability;
+        }
+
+        outcomes.len() - 1
+    }
+}
+
+impl Node {
+    /// Gets the average utility of all playouts through this node.
+    fn mean_value(&self) -> f32 {
+        if self.visits == 0 {
+            0.0
+        } else {
+            self.total_value / self.visits as f32
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conflict::TurnOrder;
+    use crate::party::Party;
+    use crate::party_member::PartyMember;
+    use crate::solver::{Solver, SolverStrategy};
+    use crate::utility_value::Personality;
+    use crate::weapon::Weapon;
+
+    fn fists(damage: f32) -> Weapon {
+        Weapon {
+            name: "their fists".to_string(),
+            damage,
+            ..Default::default()
+        }
+    }
+
+    fn strategy() -> SolverStrategy {
+        SolverStrategy::MonteCarlo {
+            iterations: 2000,
+            exploration: std::f32::consts::SQRT_2,
+        }
+    }
+
+    #[test]
+    fn monte_carlo_finds_the_winning_line() {
+        // The hero has to take out the dangerous villain first.
+        let conflict = Conflict {
+            parties: vec![
+                Party::new(0, vec![PartyMember::new(0, 20.0, fists(10.0))]),
+                Party::new(
+                    1,
+                    vec![
+                        PartyMember::new(
+                            0,
+                            15.0,
+                            Weapon {
+                                name: "a stick".to_string(),
+                                damage: 5.0,
+                                ..Default::default()
+                            },
+                        ),
+                        PartyMember::new(1, 10.0, fists(20.0)),
+                    ],
+                ),
+            ],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
+        };
+
+        let solution = Solver::engage(&conflict, strategy(), &Personality::default());
+        assert_eq!(solution.outcome, OutcomeType::Win(10.0));
+        assert!(solution.win_probability > 0.9);
+        assert!(!solution.depth_limited);
+        assert_eq!(solution.len(), 5);
+
+        match &solution.timeline[0].action {
+            AppliedAction::Targeted(action) => assert_eq!(action.target.member_id, 1),
+            action => panic!("unexpected action: {action:?}"),
+        }
+    }
+
+    #[test]
+    fn monte_carlo_estimates_chances() {
+        // The hero hits half of the time and wins, or misses and is struck down.
+        let weapon = Weapon {
+            name: "a stick".to_string(),
+            damage: 10.0,
+            hit_chance: 0.5,
+            ..Default::default()
+        };
+        let conflict = Conflict {
+            parties: vec![
+                Party::new(0, vec![PartyMember::new(0, 10.0, weapon)]),
+                Party::new(1, vec![PartyMember::new(0, 10.0, fists(10.0))]),
+            ],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
+        };
+
+        let solution = Solver::engage(&conflict, strategy(), &Personality::default());
+        let expected = match solution.outcome {
+            OutcomeType::Expected(value) => value,
+            outcome => panic!("unexpected outcome: {outcome:?}"),
+        };
+
+        assert!(
+            expected.abs() < 2.0,
+            "expected value {expected} too far from 0"
+        );
+        assert!((solution.win_probability - 0.5).abs() < 0.1);
+        assert!(solution.timeline[0].probability < 1.0);
+    }
+}
@@ -0,0 +1,265 @@
+use crate::action::AppliedAction;
+use crate::conflict::Conflict;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Orders the actions of a state so that the actions most likely to be best are
+/// searched first, which lets alpha-beta pruning cut off more of the others.
+///
+/// In order, the search tries
+/// * the best action known from the transposition table,
+/// * the action of the principal variation of the previous iteration,
+/// * the killer moves, i.e. actions that recently caused a cutoff at the same depth,
+/// * all other actions by their history, i.e. how often and how close to the root
+///   they caused a cutoff anywhere in the tree, and attacks on the enemy with the
+///   lowest health before all others.
+///
+/// Actions that are ranked equally keep the order in which they were generated.
+#[derive(Debug, Clone, Default)]
+pub struct MoveOrdering {
+    /// The principal variation of the previous iteration, one action per depth.
+    principal_variation: Vec<AppliedAction>,
+    /// Up to two actions per depth that recently caused a cutoff, the latest first.
+    killers: Vec<[Option<AppliedAction>; 2]>,
+    /// The cutoffs caused by each action, weighted by the remaining search depth.
+    history: HashMap<AppliedAction, usize>,
+    /// Whether to keep the generated order apart from the best known action.
+    unordered: bool,
+}
+
+/// The actions of a state, in the order in which they are searched.
+#[derive(Debug, Clone)]
+pub struct OrderedActions {
+    /// The ordered actions, shared by all copies of the search node.
+    actions: Rc<[AppliedAction]>,
+    /// The index of the next action to emit.
+    next: usize,
+}
+
+impl MoveOrdering {
+    /// Creates an ordering that only tries the best known action first and keeps
+    /// the generated order otherwise, to compare the heuristics against.
+    #[cfg(test)]
+    pub fn unordered() -> Self {
+        Self {
+            unordered: true,
+            ..Self::default()
+        }
+    }
+
+    /// Orders the actions of a state.
+    ///
+    /// ## Arguments
+    /// * `state` - The state in which the actions are taken.
+    /// * `actions` - The actions in the order in which they were generated.
+    /// * `depth` - The depth of the state in the search tree.
+    /// * `hash_action` - The best action known from the transposition table, if any.
+    /// * `on_principal_variation` - Whether the state lies on the principal variation
+    ///   of the previous iteration.
+    pub fn order(
+        &self,
+        state: &Conflict,
+        actions: impl Iterator<Item = AppliedAction>,
+        depth: usize,
+        hash_action: Option<&AppliedAction>,
+        on_principal_variation: bool,
+    ) -> OrderedActions {
+        let pv_action = self
+            .principal_action(depth)
+            .filter(|_| on_principal_variation && !self.unordered);
+        let killers = self.killers.get(depth).filter(|_| !self.unordered);
+
+        let mut actions: Vec<_> = actions
+            .map(|action| {
+                let rank = if Some(&action) == hash_action {
+                    0
+                } else if pv_action.is_some_and(|result| action.has_outcome(result)) {
+                    1
+                } else if let Some(index) = killers.and_then(|killers| {
+                    killers
+                        .iter()
+                        .position(|killer| killer.as_ref() == Some(&action))
+                }) {
+                    2 + index
+                } else {
+                    4
+                };
+
+                if self.unordered {
+                    return (rank, 0, 0.0, action);
+                }
+
+                let history = self.history.get(&action).copied().unwrap_or_default();
+                let health = Self::target_health(state, &action);
+                (rank, history, health, action)
+            })
+            .collect();
+
+        // Both sorts are stable, so health only decides among actions of equal rank and history.
+        actions.sort_by(|(.., health, _), (.., other_health, _)| health.total_cmp(other_health));
+        actions.sort_by_key(|&(rank, history, ..)| (rank, Reverse(history)));
+
+        OrderedActions {
+            actions: actions.into_iter().map(|(.., action)| action).collect(),
+            next: 0,
+        }
+    }
+
+    /// Remembers the principal variation of a completed iteration, so that the
+    /// next iteration searches it first.
+    ///
+    /// ## Arguments
+    /// * `actions` - The actions of the principal variation from the start, with the
+    ///   results of random actions.
+    pub fn follow(&mut self, actions: impl Iterator<Item = AppliedAction>) {
+        self.principal_variation = actions.collect();
+    }
+
+    /// Gets the action of the previous iteration's principal variation at a depth,
+    /// with the result of random actions.
+    pub fn principal_action(&self, depth: usize) -> Option<&AppliedAction> {
+        self.principal_variation.get(depth)
+    }
+
+    /// Remembers an action that caused a cutoff.
+    ///
+    /// ## Arguments
+    /// * `action` - The action that caused the cutoff.
+    /// * `depth` - The depth of the state in which the action was taken.
+    /// * `draft` - The remaining search depth below the state. Cutoffs close to
+    ///   the root save more work and weigh more.
+    pub fn record_cutoff(&mut self, action: &AppliedAction, depth: usize, draft: usize) {
+        if self.unordered {
+            return;
+        }
+
+        if self.killers.len() <= depth {
+            self.killers.resize(depth + 1, [None, None]);
+        }
+
+        let killers = &mut self.killers[depth];
+        if killers[0].as_ref() != Some(action) {
+            killers[1] = killers[0].replace(action.clone());
+        }
+
+        let history = self.history.entry(action.clone()).or_default();
+        *history = history.saturating_add(draft.saturating_mul(draft));
+    }
+
+    /// Gets the lowest health among the enemies an action attacks, or infinity
+    /// for actions not attacking anyone.
+    fn target_health(state: &Conflict, action: &AppliedAction) -> f32 {
+        let is_attack = match action {
+            AppliedAction::Targeted(targeted) => !targeted.action.targets_allies(),
+            AppliedAction::Area(area) => !area.action.targets_allies(),
+            _ => false,
+        };
+
+        if !is_attack {
+            return f32::INFINITY;
+        }
+
+        action
+            .targets()
+            .iter()
+            .map(|target| state.targeted_member(target).health)
+            .fold(f32::INFINITY, f32::min)
+    }
+}
+
+impl Iterator for OrderedActions {
+    type Item = AppliedAction;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let action = self.actions.get(self.next).cloned();
+        self.next += 1;
+        action
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::{Action, AttackRoll, Cost, SimpleAttackAction, TargetedAction};
+    use crate::conflict::TurnOrder;
+    use crate::party::{Participant, Party};
+    use crate::party_member::PartyMember;
+    use crate::weapon::Weapon;
+
+    fn member(id: usize, health: f32) -> PartyMember {
+        let weapon = Weapon {
+            name: "a club".to_string(),
+            damage: 5.0,
+            ..Default::default()
+        };
+        PartyMember::new(id, health, weapon)
+    }
+
+    fn attack(target: usize) -> AppliedAction {
+        AppliedAction::Targeted(TargetedAction {
+            action: Action::SimpleAttack(SimpleAttackAction {
+                weapon: None,
+                damage: 5.0,
+                roll: AttackRoll::Hit,
+            }),
+            source: Participant {
+                party_id: 0,
+                member_id: 0,
+            },
+            target: Participant {
+                party_id: 1,
+                member_id: target,
+            },
+            cost: Cost::default(),
+        })
+    }
+
+    fn conflict() -> Conflict {
+        Conflict {
+            parties: vec![
+                Party::new(0, vec![member(0, 20.0)]),
+                Party::new(1, vec![member(0, 15.0), member(1, 5.0), member(2, 10.0)]),
+            ],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
+        }
+    }
+
+    #[test]
+    fn weakest_enemies_are_attacked_first() {
+        let state = conflict();
+        let ordering = MoveOrdering::default();
+
+        let actions = (0..3).map(attack);
+        let ordered: Vec<_> = ordering.order(&state, actions, 0, None, false).collect();
+        assert_eq!(ordered, vec![attack(1), attack(2), attack(0)]);
+    }
+
+    #[test]
+    fn cutoffs_are_tried_first() {
+        let state = conflict();
+        let mut ordering = MoveOrdering::default();
+
+        // Killer moves only apply at the depth of their cutoff, history everywhere.
+        ordering.record_cutoff(&attack(0), 2, 1);
+        ordering.record_cutoff(&attack(2), 3, 4);
+
+        let ordered: Vec<_> = ordering
+            .order(&state, (0..3).map(attack), 2, None, false)
+            .collect();
+        assert_eq!(ordered, vec![attack(0), attack(2), attack(1)]);
+
+        let ordered: Vec<_> = ordering
+            .order(&state, (0..3).map(attack), 5, None, false)
+            .collect();
+        assert_eq!(ordered, vec![attack(2), attack(0), attack(1)]);
+
+        // The best known action and the principal variation come before everything else.
+        ordering.follow([attack(0), attack(1)].into_iter());
+        let ordered: Vec<_> = ordering
+            .order(&state, (0..3).map(attack), 1, Some(&attack(2)), true)
+            .collect();
+        assert_eq!(ordered, vec![attack(2), attack(1), attack(0)]);
+    }
+}
@@ -1,8 +1,11 @@
-use crate::party_member::PartyMember;
+use crate::party_member::{PartyMember, Row};
+use crate::utility_value::Personality;
+use serde::Serialize;
 use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
 
 /// A party, or faction in a conflict.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Party {
     /// The ID of the party. Must be unique in the conflict.
     pub id: usize,
@@ -10,12 +13,22 @@ pub struct Party {
     pub members: Vec<PartyMember>,
     /// Indicates whether the party is allowed to retreat.
     pub can_retreat: bool,
-    /// Indicates if the party has retreated from the encounter.
+    /// Indicates if the party as a whole has retreated from the encounter.
+    /// See [`Party::has_retreated`] for members getting away on their own.
     pub retreated: bool,
+    /// The alliance the party belongs to. Allied parties support each other and never
+    /// attack one another; a party without allies forms an alliance of its own.
+    pub alliance: usize,
+    /// If set, members retreat one at a time rather than the whole party at once,
+    /// each getting away with this probability. Members spend their turn on the attempt.
+    pub escape_chance: Option<f32>,
+    /// If set, the party plays for its own preferences rather than simply against the
+    /// initiating party, turning the conflict into a game that is no longer zero-sum.
+    pub personality: Option<Personality>,
 }
 
 /// A participant.
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
 pub struct Participant {
     /// The ID of the targeted party.
     pub party_id: usize,
@@ -24,6 +37,19 @@ pub struct Participant {
 }
 
 impl Party {
+    /// Creates a party forming an alliance of its own that cannot retreat.
+    pub fn new(id: usize, members: Vec<PartyMember>) -> Self {
+        Self {
+            id,
+            members,
+            can_retreat: false,
+            retreated: false,
+            alliance: id,
+            escape_chance: None,
+            personality: None,
+        }
+    }
+
     /// Makes every member unable to act in the encounter.
     pub fn retreat(&mut self) {
         debug_assert!(self.can_retreat);
@@ -33,6 +59,15 @@ impl Party {
         }
     }
 
+    /// Lets a single member leave the encounter while the rest of the party fights on.
+    pub fn escape(&mut self, member_id: usize) {
+        debug_assert!(self.can_retreat && self.escape_chance.is_some());
+        let member = &mut self.members[member_id];
+        member.escaped = true;
+        member.can_act = false;
+        member.stance = None;
+    }
+
     /// Returns `true` if the party is defeated.
     ///
     /// ## Defeat
@@ -47,9 +82,41 @@ impl Party {
         self.can_retreat
     }
 
-    /// Returns `true` if the party has retreated from the encounter.
+    /// Returns `true` if the party has retreated from the encounter, either as a whole
+    /// or with every living member having gotten away on their own.
     pub fn has_retreated(&self) -> bool {
         self.retreated
+            || (!self.is_defeated() && self.members.iter().all(|m| m.is_dead() || m.escaped))
+    }
+
+    /// Returns `true` if the party is still engaged in the conflict,
+    /// i.e. it was neither defeated nor has retreated.
+    pub fn is_engaged(&self) -> bool {
+        !self.is_defeated() && !self.has_retreated()
+    }
+
+    /// Returns `true` if both parties belong to the same alliance.
+    /// Every party is allied with itself.
+    pub fn is_allied_with(&self, other: &Party) -> bool {
+        self.alliance == other.alliance
+    }
+
+    /// Determines whether melee attacks reach a member of the party: members in the front
+    /// row always are within reach, members in the back row only once nobody is left
+    /// standing in front of them.
+    pub fn is_within_reach(&self, member: &PartyMember) -> bool {
+        member.row == Row::Front
+            || !self
+                .members
+                .iter()
+                .any(|m| m.row == Row::Front && !m.is_dead())
+    }
+
+    /// Applies the status effects of all members still present at the end of the party's turn.
+    pub fn tick_effects(&mut self) {
+        for member in self.members.iter_mut().filter(|m| !m.escaped) {
+            member.tick_effects();
+        }
     }
 
     /// Returns `true` if at least one party can still act.
@@ -72,6 +139,29 @@ impl Party {
     pub fn len(&self) -> usize {
         self.members.len()
     }
+
+    /// Returns `true` if the party has no members.
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+}
+
+/// Parties compare equal if all of their fields are equal;
+/// chances are never `NaN`.
+impl Eq for Party {}
+
+impl Hash for Party {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+        self.members.hash(state);
+        self.can_retreat.hash(state);
+        self.retreated.hash(state);
+        self.alliance.hash(state);
+        self.escape_chance.map(f32::to_bits).hash(state);
+        self.personality
+            .map(|p| [p.retreat, p.remain, p.heuristic, p.aggression].map(f32::to_bits))
+            .hash(state);
+    }
 }
 
 impl Display for Participant {
@@ -1,24 +1,174 @@
-use crate::action::{Action, SimpleAttackAction};
-use crate::weapon::Weapon;
+use crate::action::{
+    Ability, Action, AttackRoll, BuffAction, Cost, DefendAction, HealAction, SimpleAttackAction,
+};
+use crate::party::Participant;
+use crate::spell::Spell;
+use crate::status_effect::{StatusEffect, StatusEffectKind};
+use crate::weapon::{DamageType, Weapon};
+use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
 
 /// A party member.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct PartyMember {
     /// The ID of the party member. Must be uniformly increasing and unique within the party.
     pub id: usize,
     /// The amount of health. If health reaches zero, the member is dead.
     pub health: f32,
-    /// The total amount of damage taken over the course of a conflict.
+    /// The maximum amount of health. Heals never restore health beyond this value.
+    pub max_health: f32,
+    /// The total amount of damage taken over the course of a conflict,
+    /// after armor and resistances.
     pub damage_taken: f32,
+    /// The total amount of damage prevented by armor and resistances
+    /// over the course of a conflict.
+    pub damage_mitigated: f32,
     /// The weapon of choice.
     pub weapon: Weapon,
+    /// The amount by which armor reduces physical damage of every hit.
+    pub armor: f32,
+    /// The modifiers of damage of specific types, e.g. halving fire damage.
+    pub resistances: Vec<Resistance>,
+    /// The damage added to weapon attacks, e.g. by a buff.
+    pub damage_bonus: f32,
+    /// Actions the member can take in addition to attacking, e.g. healing allies.
+    pub abilities: Vec<Ability>,
+    /// The mana available for actions, e.g. spells.
+    pub mana: ResourcePool,
+    /// The stamina available for actions, e.g. heavy swings.
+    pub stamina: ResourcePool,
+    /// The spells the member can cast, along with their cooldowns.
+    pub spells: Vec<Spell>,
+    /// The status effects currently affecting the member.
+    pub effects: Vec<StatusEffect>,
     /// Whether the party member can currently act.
-    /// A member may not be able to act e.g. if they are paralyzed
-    /// or fled from the encounter.
+    /// A member may not be able to act e.g. if they fled from the encounter.
+    /// See [`PartyMember::can_act`] for temporary reasons such as being stunned.
     pub can_act: bool,
+    /// The initiative of the party member. When turns follow the
+    /// initiative order, faster members act first in each round.
+    pub speed: u32,
+    /// The row of the party's formation the member stands in.
+    pub row: Row,
+    /// The stance the member took during their last turn, if any.
+    /// Stances last until the member's next turn.
+    pub stance: Option<Stance>,
+    /// Whether the member got away from the conflict on their own, see
+    /// [`Party::escape_chance`](crate::party::Party::escape_chance).
+    /// Members who got away are out of reach.
+    pub escaped: bool,
+}
+
+/// A row of a party's formation. Melee attacks only reach members in the
+/// back row once nobody is left standing in front of them.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Row {
+    /// The row facing the opponents.
+    #[default]
+    Front,
+    /// The row behind the front row.
+    Back,
+}
+
+/// A stance a member takes on their turn, lasting until their next turn.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Stance {
+    /// The member defends, scaling all incoming damage.
+    Defending {
+        /// The factor applied to incoming damage.
+        damage_multiplier: f32,
+    },
+    /// The member guards an ally, intercepting attacks on them.
+    Guarding {
+        /// The protected ally.
+        ally: Participant,
+    },
+}
+
+/// A modifier of incoming damage of a specific type.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Resistance {
+    /// The type of damage affected.
+    pub damage_type: DamageType,
+    /// The factor applied to incoming damage of that type. Values below one
+    /// are resistances, values above one vulnerabilities; zero is an immunity.
+    pub multiplier: f32,
+}
+
+/// A resource spent on actions that regenerates over time, e.g. mana.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize)]
+pub struct ResourcePool {
+    /// The amount currently available.
+    pub current: f32,
+    /// The maximum amount. Regeneration never exceeds this value.
+    pub max: f32,
+    /// The amount regenerated at the end of every turn of the member.
+    pub regen: f32,
+}
+
+impl ResourcePool {
+    /// Creates a full pool.
+    pub fn full(max: f32, regen: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            regen,
+        }
+    }
+
+    /// Regenerates the pool by one turn's worth, up to its maximum.
+    fn regenerate(&mut self) {
+        if self.current < self.max {
+            self.current = (self.current + self.regen).min(self.max);
+        }
+    }
+
+    /// Fills the pool up to its maximum, e.g. to consider every action the member
+    /// may eventually afford.
+    pub fn refill(&mut self) {
+        self.current = self.current.max(self.max);
+    }
+}
+
+impl Row {
+    /// Gets the row a member moves to when changing position.
+    pub fn other(self) -> Self {
+        match self {
+            Row::Front => Row::Back,
+            Row::Back => Row::Front,
+        }
+    }
 }
 
 impl PartyMember {
+    /// Creates a member at full health that fights with nothing but a weapon:
+    /// without armor, abilities, spells or resources, standing in the front row.
+    pub fn new(id: usize, health: f32, weapon: Weapon) -> Self {
+        Self {
+            id,
+            health,
+            max_health: health,
+            damage_taken: 0.0,
+            damage_mitigated: 0.0,
+            weapon,
+            armor: 0.0,
+            resistances: Vec::default(),
+            damage_bonus: 0.0,
+            abilities: Vec::default(),
+            mana: ResourcePool::default(),
+            stamina: ResourcePool::default(),
+            spells: Vec::default(),
+            effects: Vec::default(),
+            can_act: true,
+            speed: 0,
+            row: Row::Front,
+            stance: None,
+            escaped: false,
+        }
+    }
+
     /// Returns `true` if the party member is dead.
     pub fn is_dead(&self) -> bool {
         self.health <= 0f32
@@ -31,6 +181,14 @@ impl PartyMember {
     pub fn handle_action(&mut self, action: &Action) -> bool {
         match action {
             Action::SimpleAttack(attack) => self.handle_simple_attack(attack),
+            Action::Heal(heal) => self.handle_heal(heal),
+            Action::Buff(buff) => self.handle_buff(buff),
+            Action::StatusEffect(effect) => self.handle_status_effect(effect),
+            Action::Spell(spell) => self.handle_action(&spell.effect),
+            Action::Defend(defend) => self.handle_defend(defend),
+            // Guarding changes the stance of the guard rather than that of
+            // the protected ally, see [`PartyMember::guard`].
+            Action::Guard => !self.is_dead(),
         }
     }
 
@@ -47,36 +205,280 @@ impl PartyMember {
             return false;
         }
 
+        let damage = self.mitigate(attack);
+        self.damage_mitigated += attack.damage - damage;
+        self.take_damage(damage);
+
+        // Weapons may apply status effects on a hit, e.g. a poisoned dagger.
+        if let Some(weapon) = attack
+            .weapon
+            .as_ref()
+            .filter(|_| attack.roll != AttackRoll::Miss)
+        {
+            for effect in &weapon.effects {
+                self.handle_status_effect(effect);
+            }
+        }
+
+        true
+    }
+
+    /// Determines the damage an attack inflicts on the member. Resistances scale the
+    /// damage of their type, after which armor reduces physical damage. Attacks
+    /// without a weapon ignore both. A defending member takes reduced damage either way.
+    pub fn mitigate(&self, attack: &SimpleAttackAction) -> f32 {
+        let damage = match &attack.weapon {
+            Some(weapon) => {
+                let damage_type = weapon.damage_type;
+                let damage = attack.damage * self.damage_multiplier(damage_type);
+                if damage_type.is_physical() {
+                    // Armor never turns a hit into a heal, nor adds to it.
+                    (damage - self.armor).max(0.0).min(damage.max(0.0))
+                } else {
+                    damage
+                }
+            }
+            None => attack.damage,
+        };
+
+        match self.stance {
+            Some(Stance::Defending { damage_multiplier }) => damage * damage_multiplier,
+            _ => damage,
+        }
+    }
+
+    /// Gets the factor applied to incoming damage of a type.
+    pub fn damage_multiplier(&self, damage_type: DamageType) -> f32 {
+        self.resistances
+            .iter()
+            .filter(|r| r.damage_type == damage_type)
+            .map(|r| r.multiplier)
+            .product()
+    }
+
+    /// Inflicts damage on the member. Any damage wakes a sleeping member.
+    fn take_damage(&mut self, damage: f32) {
         // We ensure that the health is never negative.
-        self.health = (self.health - attack.damage).max(0.0);
+        self.health = (self.health - damage).max(0.0);
 
         // We just sum up the damage taken regardless of whether
         // it would actually "fit" the health, i.e. a 100 damage on 10
         // health would still be counted 100 instead of 10.
         // This has no real reason apart from being much cooler to look at.
-        self.damage_taken += attack.damage;
+        self.damage_taken += damage;
+
+        if damage > 0.0 {
+            self.effects.retain(|e| e.kind != StatusEffectKind::Sleep);
+        }
+    }
+
+    /// Handles a heal.
+    ///
+    /// ## Returns
+    /// `true` if the action could be applied; `false` otherwise. A heal will not be applied
+    /// if the member is dead or already at full health.
+    fn handle_heal(&mut self, heal: &HealAction) -> bool {
+        if self.is_dead() || self.health >= self.max_health {
+            return false;
+        }
+
+        // Healing never exceeds the maximum health, but it also does
+        // not undo the damage taken; that one is for the history books.
+        self.health = (self.health + heal.amount).min(self.max_health);
+        true
+    }
+
+    /// Handles a buff.
+    ///
+    /// ## Returns
+    /// `true` if the action could be applied; `false` otherwise. A buff will not be applied
+    /// if the member is dead or already has an equal or stronger buff.
+    fn handle_buff(&mut self, buff: &BuffAction) -> bool {
+        if self.is_dead() || self.damage_bonus >= buff.damage_bonus {
+            return false;
+        }
+
+        self.damage_bonus = buff.damage_bonus;
+        true
+    }
+
+    /// Handles a defensive stance.
+    ///
+    /// ## Returns
+    /// `true` if the action could be applied; `false` otherwise. A member will not
+    /// defend if they are dead.
+    fn handle_defend(&mut self, defend: &DefendAction) -> bool {
+        if self.is_dead() {
+            return false;
+        }
+
+        self.stance = Some(Stance::Defending {
+            damage_multiplier: defend.damage_multiplier,
+        });
+        true
+    }
+
+    /// Lets the member guard an ally until their next turn.
+    pub fn guard(&mut self, ally: Participant) {
+        self.stance = Some(Stance::Guarding { ally });
+    }
+
+    /// Determines whether the member intercepts attacks on the ally,
+    /// i.e. whether they guard the ally and are able to act.
+    pub fn is_guarding(&self, ally: &Participant) -> bool {
+        self.can_act() && matches!(&self.stance, Some(Stance::Guarding { ally: a }) if a == ally)
+    }
+
+    /// Handles a status effect.
+    ///
+    /// ## Returns
+    /// `true` if the action could be applied; `false` otherwise. An effect will not be applied
+    /// if the member is dead or already affected by an effect of the same kind.
+    fn handle_status_effect(&mut self, effect: &StatusEffect) -> bool {
+        if !self.is_applicable(&Action::StatusEffect(effect.clone())) {
+            return false;
+        }
+
+        self.effects.push(effect.clone());
+        true
+    }
+
+    /// Applies all status effects at the end of the member's turn
+    /// and removes the effects that wore off. Resources regenerate as well.
+    pub fn tick_effects(&mut self) {
+        if self.is_dead() {
+            return;
+        }
+
+        self.mana.regenerate();
+        self.stamina.regenerate();
+        for spell in self.spells.iter_mut() {
+            spell.ready_in = spell.ready_in.saturating_sub(1);
+        }
+
+        let mut poison = 0.0;
+        let mut regen = 0.0;
+        for effect in self.effects.iter_mut() {
+            match effect.kind {
+                StatusEffectKind::Poison { damage } => poison += damage,
+                StatusEffectKind::Regen { amount } => regen += amount,
+                StatusEffectKind::Stun | StatusEffectKind::Sleep => {}
+            }
+            effect.duration = effect.duration.saturating_sub(1);
+        }
+        self.effects.retain(|e| e.duration > 0);
 
-        return true;
+        if regen > 0.0 {
+            self.health = (self.health + regen).min(self.max_health.max(self.health));
+        }
+        if poison > 0.0 {
+            self.take_damage(poison);
+        }
+    }
+
+    /// Determines whether the member has enough resources to pay for an action.
+    pub fn can_afford(&self, cost: &Cost) -> bool {
+        self.mana.current >= cost.mana && self.stamina.current >= cost.stamina
+    }
+
+    /// Spends the resources for an action.
+    pub fn spend(&mut self, cost: &Cost) {
+        debug_assert!(self.can_afford(cost));
+        self.mana.current -= cost.mana;
+        self.stamina.current -= cost.stamina;
+    }
+
+    /// Puts a spell on cooldown after it was cast.
+    pub fn start_cooldown(&mut self, name: &str) {
+        if let Some(spell) = self.spells.iter_mut().find(|s| s.name == name) {
+            spell.ready_in = spell.cooldown;
+        }
     }
 
     /// Returns an iterator listing all possible actions the party
-    /// member can take.
+    /// member can take, skipping actions they cannot afford.
     pub fn actions(self) -> AttackIterator {
         AttackIterator::new(self)
     }
 
     /// Determines whether the current member can act.
     pub fn can_act(&self) -> bool {
+        self.takes_turns() && !self.effects.iter().any(StatusEffect::is_incapacitating)
+    }
+
+    /// Determines whether the current member still takes turns in the conflict,
+    /// even if they may be unable to act during them, e.g. when stunned.
+    pub fn takes_turns(&self) -> bool {
         self.can_act && !self.is_dead()
     }
 
     /// Determines whether the action is applicable to this member.
-    pub fn is_applicable(&self, _action: &Action) -> bool {
-        !self.is_dead()
+    pub fn is_applicable(&self, action: &Action) -> bool {
+        if self.is_dead() || self.escaped {
+            return false;
+        }
+
+        match action {
+            Action::SimpleAttack(_) => true,
+            Action::Heal(_) => self.health < self.max_health,
+            Action::Buff(buff) => self.damage_bonus < buff.damage_bonus,
+            Action::StatusEffect(effect) => !self.effects.iter().any(|e| e.is_same_kind(effect)),
+            Action::Spell(spell) => self.is_applicable(&spell.effect),
+            Action::Defend(_) | Action::Guard => true,
+        }
     }
 }
 
-/// An iterator for attack actions, i.e. actions targeting a single opponent.
+/// Stances compare equal if all of their fields are equal;
+/// multipliers are never `NaN`.
+impl Eq for Stance {}
+
+impl Hash for Stance {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Stance::Defending { damage_multiplier } => damage_multiplier.to_bits().hash(state),
+            Stance::Guarding { ally } => ally.hash(state),
+        }
+    }
+}
+
+/// Party members compare equal if all of their fields are equal;
+/// health values are never `NaN`.
+impl Eq for PartyMember {}
+
+impl Hash for PartyMember {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+        self.health.to_bits().hash(state);
+        self.max_health.to_bits().hash(state);
+        self.damage_taken.to_bits().hash(state);
+        self.damage_mitigated.to_bits().hash(state);
+        self.weapon.hash(state);
+        self.armor.to_bits().hash(state);
+        for resistance in &self.resistances {
+            resistance.damage_type.hash(state);
+            resistance.multiplier.to_bits().hash(state);
+        }
+        self.damage_bonus.to_bits().hash(state);
+        self.abilities.hash(state);
+        self.spells.hash(state);
+        for pool in [&self.mana, &self.stamina] {
+            pool.current.to_bits().hash(state);
+            pool.max.to_bits().hash(state);
+            pool.regen.to_bits().hash(state);
+        }
+        self.effects.hash(state);
+        self.can_act.hash(state);
+        self.speed.hash(state);
+        self.row.hash(state);
+        self.stance.hash(state);
+        self.escaped.hash(state);
+    }
+}
+
+/// An iterator for the actions of a party member, i.e. attacks, abilities and spells
+/// that are off cooldown.
 #[derive(Debug, Clone)]
 pub struct AttackIterator {
     member: PartyMember,
@@ -92,31 +494,55 @@ impl AttackIterator {
 
 /// Implements the [`AttackIterator`] as a state machine.
 impl Iterator for AttackIterator {
-    type Item = Action;
+    type Item = Ability;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let state = self.index;
-        match state {
-            0 => {
-                let damage = self.member.weapon.damage();
-                let action = Action::SimpleAttack(SimpleAttackAction {
-                    weapon: Some(self.member.weapon.clone()),
-                    damage,
-                });
+        loop {
+            let state = self.index;
+            self.index += 1;
 
-                self.index += 1;
-                Some(action)
-            }
-            1 => {
-                let action = Action::SimpleAttack(SimpleAttackAction {
-                    weapon: None,
-                    damage: 1.0,
-                });
-
-                self.index += 1;
-                Some(action)
+            let ability = match state {
+                0 => {
+                    let damage = self.member.weapon.damage + self.member.damage_bonus;
+                    Ability {
+                        action: Action::SimpleAttack(SimpleAttackAction {
+                            weapon: Some(self.member.weapon.clone()),
+                            damage,
+                            roll: AttackRoll::Hit,
+                        }),
+                        cost: self.member.weapon.cost,
+                    }
+                }
+                1 => Ability {
+                    action: Action::SimpleAttack(SimpleAttackAction {
+                        weapon: None,
+                        damage: 1.0,
+                        roll: AttackRoll::Hit,
+                    }),
+                    cost: Cost::default(),
+                },
+                index if index - 2 < self.member.abilities.len() => {
+                    self.member.abilities[index - 2].clone()
+                }
+                index => {
+                    let spell = self
+                        .member
+                        .spells
+                        .get(index - 2 - self.member.abilities.len())?;
+                    if !spell.is_ready() {
+                        continue;
+                    }
+
+                    Ability {
+                        action: spell.cast(),
+                        cost: spell.cost,
+                    }
+                }
+            };
+
+            if self.member.can_afford(&ability.cost) {
+                return Some(ability);
             }
-            _ => None,
         }
     }
 }
@@ -124,23 +550,26 @@ impl Iterator for AttackIterator {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::weapon::Stick;
+    use crate::status_effect::StatusEffect;
 
     #[test]
     fn a_lot_of_damage() {
-        let mut member = PartyMember {
-            id: 0,
-            health: 100.0,
-            damage_taken: 0.0,
-            weapon: Weapon::Stick(Stick { damage: 0.0 }),
-            can_act: true,
-        };
+        let mut member = PartyMember::new(
+            0,
+            100.0,
+            Weapon {
+                name: "a stick".to_string(),
+                damage: 0.0,
+                ..Default::default()
+            },
+        );
 
         // Apply more damage than the subject has health.
         let damage_dealt = member.health + 100.0;
         let attack = SimpleAttackAction {
             weapon: None,
             damage: damage_dealt,
+            roll: AttackRoll::Hit,
         };
         member.handle_action(&Action::SimpleAttack(attack));
 
@@ -152,30 +581,229 @@ mod tests {
     }
 
     #[test]
-    fn attack_iterator() {
+    fn armor_handles_harmless_attacks() {
+        let weapon = Weapon {
+            name: "a stick".to_string(),
+            damage: 0.0,
+            ..Default::default()
+        };
         let member = PartyMember {
-            id: 0,
-            health: 100.0,
-            damage_taken: 0.0,
-            weapon: Weapon::Stick(Stick { damage: 0.0 }),
-            can_act: true,
+            armor: 2.0,
+            ..PartyMember::new(0, 10.0, weapon.clone())
         };
 
-        let mut iter = member.clone().actions();
+        for damage in [0.0, -3.0] {
+            let attack = SimpleAttackAction {
+                weapon: Some(weapon.clone()),
+                damage,
+                roll: AttackRoll::Hit,
+            };
+            assert_eq!(member.mitigate(&attack), 0.0);
+        }
+    }
+
+    #[test]
+    fn attack_iterator() {
+        let member = PartyMember::new(
+            0,
+            100.0,
+            Weapon {
+                name: "a stick".to_string(),
+                damage: 0.0,
+                ..Default::default()
+            },
+        );
+
+        let mut iter = member.clone().actions().map(|ability| ability.action);
         assert_eq!(
             iter.next(),
             Some(Action::SimpleAttack(SimpleAttackAction {
                 weapon: Some(member.weapon),
-                damage: 0.0
+                damage: 0.0,
+                roll: AttackRoll::Hit,
             }))
         );
         assert_eq!(
             iter.next(),
             Some(Action::SimpleAttack(SimpleAttackAction {
                 weapon: None,
-                damage: 1.0
+                damage: 1.0,
+                roll: AttackRoll::Hit,
             }))
         );
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn heals_and_buffs() {
+        let mut member = PartyMember {
+            max_health: 10.0,
+            damage_taken: 5.0,
+            ..PartyMember::new(
+                0,
+                5.0,
+                Weapon {
+                    name: "a stick".to_string(),
+                    damage: 2.0,
+                    ..Default::default()
+                },
+            )
+        };
+
+        // Heals are capped at the maximum health and do not apply at full health.
+        let heal = Action::Heal(HealAction { amount: 20.0 });
+        assert!(member.is_applicable(&heal));
+        assert!(member.handle_action(&heal));
+        assert_eq!(member.health, 10.0);
+        assert_eq!(member.damage_taken, 5.0);
+        assert!(!member.is_applicable(&heal));
+        assert!(!member.handle_action(&heal));
+
+        // Buffs add to the weapon damage and do not stack.
+        let buff = Action::Buff(BuffAction { damage_bonus: 3.0 });
+        assert!(member.handle_action(&buff));
+        assert!(!member.is_applicable(&buff));
+        let attack = member.clone().actions().next().unwrap();
+        assert_eq!(attack.action.max_damage(), 5.0);
+    }
+
+    #[test]
+    fn status_effects_tick() {
+        let mut member = PartyMember::new(
+            0,
+            10.0,
+            Weapon {
+                name: "a stick".to_string(),
+                damage: 2.0,
+                ..Default::default()
+            },
+        );
+
+        let poison = Action::StatusEffect(StatusEffect {
+            kind: StatusEffectKind::Poison { damage: 3.0 },
+            duration: 2,
+        });
+        let sleep = Action::StatusEffect(StatusEffect {
+            kind: StatusEffectKind::Sleep,
+            duration: 5,
+        });
+        assert!(member.handle_action(&sleep));
+        assert!(!member.can_act());
+        assert!(member.takes_turns());
+
+        // Effects of the same kind do not stack.
+        assert!(member.handle_action(&poison));
+        assert!(!member.handle_action(&poison));
+
+        // Poison deals damage at the end of every turn, waking the member up.
+        member.tick_effects();
+        assert_eq!((member.health, member.damage_taken), (7.0, 3.0));
+        assert!(member.can_act());
+
+        member.tick_effects();
+        assert_eq!(member.health, 4.0);
+        assert!(member.effects.is_empty());
+
+        member.tick_effects();
+        assert_eq!(member.health, 4.0);
+    }
+
+    #[test]
+    fn armor_and_resistances_mitigate_damage() {
+        let mut member = PartyMember {
+            armor: 2.0,
+            resistances: vec![Resistance {
+                damage_type: DamageType::Fire,
+                multiplier: 0.5,
+            }],
+            ..PartyMember::new(0, 20.0, Weapon::default())
+        };
+
+        let attack = |damage_type, damage| {
+            Action::SimpleAttack(SimpleAttackAction {
+                weapon: Some(Weapon {
+                    damage,
+                    damage_type,
+                    ..Default::default()
+                }),
+                damage,
+                roll: AttackRoll::Hit,
+            })
+        };
+
+        // Armor reduces physical damage, but never below zero.
+        assert!(member.handle_action(&attack(DamageType::Slashing, 5.0)));
+        assert_eq!((member.health, member.damage_mitigated), (17.0, 2.0));
+        assert!(member.handle_action(&attack(DamageType::Piercing, 1.0)));
+        assert_eq!((member.health, member.damage_mitigated), (17.0, 3.0));
+
+        // Resistances scale elemental damage, which armor does not affect.
+        assert!(member.handle_action(&attack(DamageType::Fire, 6.0)));
+        assert_eq!((member.health, member.damage_mitigated), (14.0, 6.0));
+        assert_eq!(member.damage_taken, 6.0);
+
+        // Attacks without a weapon bypass all defenses.
+        let stare = Action::SimpleAttack(SimpleAttackAction {
+            weapon: None,
+            damage: 4.0,
+            roll: AttackRoll::Hit,
+        });
+        assert!(member.handle_action(&stare));
+        assert_eq!((member.health, member.damage_mitigated), (10.0, 6.0));
+
+        // Defending scales the damage left after armor and resistances.
+        let defend = Action::Defend(DefendAction {
+            damage_multiplier: 0.5,
+        });
+        assert!(member.handle_action(&defend));
+        assert!(member.handle_action(&attack(DamageType::Slashing, 6.0)));
+        assert_eq!((member.health, member.damage_mitigated), (8.0, 10.0));
+        assert!(member.handle_action(&stare));
+        assert_eq!((member.health, member.damage_mitigated), (6.0, 12.0));
+    }
+
+    #[test]
+    fn actions_cost_resources() {
+        let heal = Action::Heal(HealAction { amount: 5.0 });
+        let mut member = PartyMember {
+            abilities: vec![Ability {
+                action: heal.clone(),
+                cost: Cost {
+                    mana: 2.0,
+                    stamina: 0.0,
+                },
+            }],
+            mana: ResourcePool::full(5.0, 1.0),
+            ..PartyMember::new(
+                0,
+                10.0,
+                Weapon {
+                    name: "a fireball".to_string(),
+                    damage: 3.0,
+                    cost: Cost {
+                        mana: 4.0,
+                        stamina: 0.0,
+                    },
+                    ..Default::default()
+                },
+            )
+        };
+
+        assert_eq!(member.clone().actions().count(), 3);
+
+        // Once the mana runs low, only the cheaper actions remain.
+        member.spend(&member.weapon.cost.clone());
+        let actions: Vec<_> = member.clone().actions().map(|a| a.action).collect();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].max_damage(), 1.0);
+
+        // Mana regenerates at the end of every turn, up to the maximum.
+        member.tick_effects();
+        assert_eq!(member.mana.current, 2.0);
+        assert_eq!(member.clone().actions().last().unwrap().action, heal);
+        for _ in 0..5 {
+            member.tick_effects();
+        }
+        assert_eq!(member.mana.current, 5.0);
+    }
 }
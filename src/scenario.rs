@@ -0,0 +1,1273 @@
+use crate::action::{
+    Ability, Action, AttackRoll, BuffAction, Cost, DefendAction, HealAction, SimpleAttackAction,
+};
+use crate::catalog::WeaponCatalog;
+use crate::conflict::{Conflict, TurnOrder};
+use crate::party::Party;
+use crate::party_member::{PartyMember, Resistance, ResourcePool, Row};
+use crate::spell::Spell;
+use crate::status_effect::{StatusEffect, StatusEffectKind};
+use crate::utility_value::Personality;
+use crate::weapon::{DamageType, Dice, Weapon, WeaponArea, WeaponRange};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+/// An encounter loaded from a scenario file.
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    /// The conflict to resolve.
+    pub conflict: Conflict,
+    /// The optional names of each party member, indexed by party ID and member ID.
+    pub names: Vec<Vec<Option<String>>>,
+}
+
+/// An error raised while loading a scenario or a weapon catalog.
+#[derive(Debug)]
+pub enum ScenarioError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The file extension does not name a supported format.
+    UnsupportedFormat(String),
+    /// The file could not be parsed.
+    Parse {
+        /// The path to the offending field.
+        field: String,
+        /// The parser's error message.
+        message: String,
+    },
+    /// A field holds an invalid value.
+    Invalid {
+        /// The path to the offending field.
+        field: String,
+        /// A description of the problem.
+        message: String,
+    },
+}
+
+/// The file representation of a scenario.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScenarioDefinition {
+    /// The party initiating the conflict.
+    initiator: PartyDefinition,
+    /// The opposing party.
+    opponent: PartyDefinition,
+    /// Further parties joining the conflict, taking their turns after the opponent.
+    #[serde(default)]
+    others: Vec<PartyDefinition>,
+    /// The order in which members take their turns.
+    #[serde(default)]
+    turn_order: TurnOrderDefinition,
+}
+
+/// The file representation of the turn order.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum TurnOrderDefinition {
+    /// The parties alternate after every action.
+    #[default]
+    Alternating,
+    /// Members act in order of their speed.
+    Initiative,
+}
+
+/// The file representation of a party.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PartyDefinition {
+    /// Whether the party is allowed to retreat.
+    #[serde(default)]
+    can_retreat: bool,
+    /// The name of the alliance the party belongs to. Parties without an alliance
+    /// fight every other party.
+    alliance: Option<String>,
+    /// If set, members retreat one at a time, each getting away with this probability.
+    escape_chance: Option<f32>,
+    /// If set, the party plays for its own preferences rather than against the initiator.
+    personality: Option<PersonalityDefinition>,
+    /// The members of the party.
+    members: Vec<MemberDefinition>,
+}
+
+/// The file representation of a party's personality.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum PersonalityDefinition {
+    /// The standard weighing of outcomes.
+    Standard,
+    /// Values damage dealt as much as health kept.
+    Aggressive,
+    /// Values survival, happy to retreat or to see the opponents leave.
+    Cautious,
+}
+
+/// The file representation of a party member.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MemberDefinition {
+    /// The name of the member. If unset, a name may be generated.
+    name: Option<String>,
+    /// The initial health of the member.
+    health: f32,
+    /// The maximum health of the member. Defaults to the initial health.
+    max_health: Option<f32>,
+    /// The weapon of the member.
+    weapon: WeaponDefinition,
+    /// The amount by which armor reduces physical damage of every hit.
+    #[serde(default)]
+    armor: f32,
+    /// The factors applied to incoming damage by damage type,
+    /// e.g. `0.5` to halve or `2` to double it.
+    #[serde(default)]
+    resistances: BTreeMap<DamageType, f32>,
+    /// The initiative of the member.
+    #[serde(default)]
+    speed: u32,
+    /// The row of the party's formation the member stands in. Once any member
+    /// is assigned a row, members may change rows during the encounter.
+    row: Option<Row>,
+    /// The mana available to the member, initially and at most.
+    #[serde(default)]
+    mana: f32,
+    /// The mana regenerated at the end of every turn of the member.
+    #[serde(default)]
+    mana_regen: f32,
+    /// The stamina available to the member, initially and at most.
+    #[serde(default)]
+    stamina: f32,
+    /// The stamina regenerated at the end of every turn of the member.
+    #[serde(default)]
+    stamina_regen: f32,
+    /// The supporting actions the member can take.
+    #[serde(default)]
+    abilities: Vec<AbilityDefinition>,
+    /// The spells the member can cast.
+    #[serde(default)]
+    spells: Vec<SpellDefinition>,
+}
+
+/// The file representation of a supporting action a member can take.
+#[derive(Debug, Deserialize)]
+struct AbilityDefinition {
+    /// The action.
+    #[serde(flatten)]
+    action: ActionDefinition,
+    /// The resources spent on every use of the ability.
+    #[serde(default)]
+    cost: CostDefinition,
+}
+
+/// The file representation of the resources spent on an action.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct CostDefinition {
+    /// The mana spent.
+    #[serde(default)]
+    mana: f32,
+    /// The stamina spent.
+    #[serde(default)]
+    stamina: f32,
+}
+
+/// The file representation of a supporting action.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub(crate) enum ActionDefinition {
+    /// Restores health of an ally.
+    Heal {
+        /// The amount of health restored.
+        amount: f32,
+    },
+    /// Increases the damage of an ally's weapon attacks.
+    Buff {
+        /// The damage added to each weapon attack.
+        damage_bonus: f32,
+    },
+    /// Poisons an opponent.
+    Poison {
+        /// The damage dealt per turn.
+        damage: f32,
+        /// The number of turns the effect lasts.
+        duration: u32,
+    },
+    /// Lets an ally regenerate health.
+    Regen {
+        /// The amount of health restored per turn.
+        amount: f32,
+        /// The number of turns the effect lasts.
+        duration: u32,
+    },
+    /// Stuns an opponent.
+    Stun {
+        /// The number of turns the effect lasts.
+        duration: u32,
+    },
+    /// Puts an opponent to sleep.
+    Sleep {
+        /// The number of turns the effect lasts.
+        duration: u32,
+    },
+    /// Damages an opponent. Only available to spells.
+    Attack {
+        /// The damage dealt.
+        damage: f32,
+        /// The kind of damage dealt.
+        #[serde(default)]
+        damage_type: DamageType,
+    },
+    /// Reduces the damage taken until the member's next turn. Only available to abilities.
+    Defend {
+        /// The factor applied to incoming damage, between 0 and 1.
+        damage_multiplier: f32,
+    },
+    /// Intercepts attacks on an ally until the member's next turn.
+    /// Only available to abilities.
+    Guard,
+}
+
+/// The file representation of a spell.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SpellDefinition {
+    /// The name of the spell, unique among the spells of the member.
+    name: String,
+    /// The effect of the spell on each of its targets.
+    effect: ActionDefinition,
+    /// The resources spent on every cast.
+    #[serde(default)]
+    cost: CostDefinition,
+    /// The members affected by a single cast.
+    #[serde(default)]
+    target: WeaponArea,
+    /// The number of turns of the caster after a cast before the spell can be cast again.
+    #[serde(default)]
+    cooldown: u32,
+}
+
+/// The file representation of a weapon: either a reference to a catalog weapon
+/// with optional overrides, or a catalog entry itself.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct WeaponDefinition {
+    /// The ID of the catalog weapon to start from. Required in scenarios,
+    /// not allowed in catalogs.
+    #[serde(rename = "type")]
+    base: Option<String>,
+    /// The name of the weapon as used in a sentence, e.g. "a stick".
+    name: Option<String>,
+    /// The amount of damage dealt on a successful hit.
+    damage: Option<f32>,
+    /// The dice rolled for additional damage on a hit, e.g. `1d6`.
+    dice: Option<String>,
+    /// The kind of damage dealt.
+    damage_type: Option<DamageType>,
+    /// The distance at which the weapon can be used.
+    range: Option<WeaponRange>,
+    /// The opponents hit by a single attack.
+    area: Option<WeaponArea>,
+    /// The probability of hitting the target.
+    hit_chance: Option<f32>,
+    /// The probability of a hit being critical.
+    critical_chance: Option<f32>,
+    /// The status effects applied to the target on a hit.
+    effects: Option<Vec<ActionDefinition>>,
+    /// The resources spent on every attack.
+    cost: Option<CostDefinition>,
+}
+
+impl Scenario {
+    /// Loads a scenario from a file. The format is determined by the
+    /// file extension, which must be either `.toml` or `.json`.
+    ///
+    /// ## Arguments
+    /// * `path` - The path to the scenario file.
+    /// * `catalog` - The weapons the scenario may refer to.
+    pub fn load(path: &Path, catalog: &WeaponCatalog) -> Result<Self, ScenarioError> {
+        match read_file(path)? {
+            (text, FileFormat::Toml) => Self::from_toml(&text, catalog),
+            (text, FileFormat::Json) => Self::from_json(&text, catalog),
+        }
+    }
+
+    /// Parses a scenario from TOML.
+    pub fn from_toml(text: &str, catalog: &WeaponCatalog) -> Result<Self, ScenarioError> {
+        parse_toml::<ScenarioDefinition>(text)?.build(catalog)
+    }
+
+    /// Parses a scenario from JSON.
+    pub fn from_json(text: &str, catalog: &WeaponCatalog) -> Result<Self, ScenarioError> {
+        parse_json::<ScenarioDefinition>(text)?.build(catalog)
+    }
+}
+
+/// The format of a scenario or catalog file.
+pub(crate) enum FileFormat {
+    /// A `.toml` file.
+    Toml,
+    /// A `.json` file.
+    Json,
+}
+
+/// Reads a file, determining its format by the file extension.
+pub(crate) fn read_file(path: &Path) -> Result<(String, FileFormat), ScenarioError> {
+    let format = match path.extension().and_then(|e| e.to_str()) {
+        Some("toml") => FileFormat::Toml,
+        Some("json") => FileFormat::Json,
+        _ => return Err(ScenarioError::UnsupportedFormat(path.display().to_string())),
+    };
+
+    let text = std::fs::read_to_string(path).map_err(ScenarioError::Io)?;
+    Ok((text, format))
+}
+
+/// Parses a definition from TOML.
+pub(crate) fn parse_toml<T: DeserializeOwned>(text: &str) -> Result<T, ScenarioError> {
+    let deserializer = toml::Deserializer::new(text);
+    serde_path_to_error::deserialize(deserializer).map_err(ScenarioError::from_parse)
+}
+
+/// Parses a definition from JSON.
+pub(crate) fn parse_json<T: DeserializeOwned>(text: &str) -> Result<T, ScenarioError> {
+    let mut deserializer = serde_json::Deserializer::from_str(text);
+    serde_path_to_error::deserialize(&mut deserializer).map_err(ScenarioError::from_parse)
+}
+
+impl ScenarioDefinition {
+    /// Validates the definition and builds the scenario from it.
+    fn build(self, catalog: &WeaponCatalog) -> Result<Scenario, ScenarioError> {
+        let formations = [&self.initiator, &self.opponent]
+            .into_iter()
+            .chain(&self.others)
+            .flat_map(|party| &party.members)
+            .any(|member| member.row.is_some());
+
+        let definitions = [
+            ("initiator".to_string(), self.initiator),
+            ("opponent".to_string(), self.opponent),
+        ]
+        .into_iter()
+        .chain(
+            self.others
+                .into_iter()
+                .enumerate()
+                .map(|(index, party)| (format!("others[{index}]"), party)),
+        );
+
+        let mut alliances: Vec<Option<String>> = Vec::default();
+        let mut parties = Vec::default();
+        let mut names = Vec::default();
+        for (id, (field, definition)) in definitions.enumerate() {
+            // Allied parties share the ID of the first party naming the alliance.
+            let alliance = definition
+                .alliance
+                .as_ref()
+                .and_then(|name| alliances.iter().position(|a| a.as_ref() == Some(name)))
+                .unwrap_or(id);
+            if id == 1 && alliance == 0 {
+                return Err(ScenarioError::invalid(
+                    format!("{field}.alliance"),
+                    "the opponent must not be allied with the initiator",
+                ));
+            }
+
+            alliances.push(definition.alliance.clone());
+            let (party, party_names) = definition.build(id, alliance, &field, catalog)?;
+            parties.push(party);
+            names.push(party_names);
+        }
+
+        Ok(Scenario {
+            conflict: Conflict {
+                parties,
+                turn_order: match self.turn_order {
+                    TurnOrderDefinition::Alternating => TurnOrder::Alternating { party: 0 },
+                    TurnOrderDefinition::Initiative => TurnOrder::Initiative { position: 0 },
+                },
+                formations,
+            },
+            names,
+        })
+    }
+}
+
+impl PartyDefinition {
+    /// Validates the definition and builds the party from it.
+    ///
+    /// ## Arguments
+    /// * `id` - The ID of the party in the conflict.
+    /// * `alliance` - The ID of the alliance the party belongs to.
+    /// * `field` - The path to the party definition, used in error messages.
+    /// * `catalog` - The weapons the members may refer to.
+    fn build(
+        self,
+        id: usize,
+        alliance: usize,
+        field: &str,
+        catalog: &WeaponCatalog,
+    ) -> Result<(Party, Vec<Option<String>>), ScenarioError> {
+        if self.members.is_empty() {
+            return Err(ScenarioError::invalid(
+                format!("{field}.members"),
+                "a party needs at least one member",
+            ));
+        }
+
+        if let Some(chance) = self.escape_chance {
+            if !(chance > 0.0 && chance <= 1.0) {
+                return Err(ScenarioError::invalid(
+                    format!("{field}.escape_chance"),
+                    "chances must be above 0 and at most 1",
+                ));
+            }
+            if !self.can_retreat {
+                return Err(ScenarioError::invalid(
+                    format!("{field}.escape_chance"),
+                    "only parties that can retreat may escape",
+                ));
+            }
+        }
+
+        let mut members = Vec::with_capacity(self.members.len());
+        let mut names = Vec::with_capacity(self.members.len());
+        for (member_id, member) in self.members.into_iter().enumerate() {
+            let field = format!("{field}.members[{member_id}]");
+            if !member.health.is_finite() || member.health <= 0.0 {
+                return Err(ScenarioError::invalid(
+                    format!("{field}.health"),
+                    "health must be a positive number",
+                ));
+            }
+
+            let max_health = member.max_health.unwrap_or(member.health);
+            if !max_health.is_finite() || max_health < member.health {
+                return Err(ScenarioError::invalid(
+                    format!("{field}.max_health"),
+                    "maximum health must not be less than the initial health",
+                ));
+            }
+
+            if !member.armor.is_finite() || member.armor < 0.0 {
+                return Err(ScenarioError::invalid(
+                    format!("{field}.armor"),
+                    "armor must be a non-negative number",
+                ));
+            }
+
+            let mut resistances = Vec::with_capacity(member.resistances.len());
+            for (damage_type, multiplier) in member.resistances {
+                if !multiplier.is_finite() || multiplier < 0.0 {
+                    let name = format!("{damage_type:?}").to_lowercase();
+                    return Err(ScenarioError::invalid(
+                        format!("{field}.resistances.{name}"),
+                        "damage multipliers must be non-negative numbers",
+                    ));
+                }
+
+                resistances.push(Resistance {
+                    damage_type,
+                    multiplier,
+                });
+            }
+
+            for (name, value) in [
+                ("mana", member.mana),
+                ("mana_regen", member.mana_regen),
+                ("stamina", member.stamina),
+                ("stamina_regen", member.stamina_regen),
+            ] {
+                if !value.is_finite() || value < 0.0 {
+                    return Err(ScenarioError::invalid(
+                        format!("{field}.{name}"),
+                        "resources must be non-negative numbers",
+                    ));
+                }
+            }
+
+            let abilities = member
+                .abilities
+                .into_iter()
+                .enumerate()
+                .map(|(index, ability)| ability.build(&format!("{field}.abilities[{index}]")))
+                .collect::<Result<_, _>>()?;
+
+            let mut spells: Vec<Spell> = Vec::with_capacity(member.spells.len());
+            for (index, spell) in member.spells.into_iter().enumerate() {
+                let field = format!("{field}.spells[{index}]");
+                let spell = spell.build(&field)?;
+                if spells.iter().any(|s| s.name == spell.name) {
+                    return Err(ScenarioError::invalid(
+                        format!("{field}.name"),
+                        "spell names must be unique",
+                    ));
+                }
+
+                spells.push(spell);
+            }
+
+            members.push(PartyMember {
+                id: member_id,
+                health: member.health,
+                max_health,
+                damage_taken: 0.0,
+                damage_mitigated: 0.0,
+                weapon: member.weapon.build(&format!("{field}.weapon"), catalog)?,
+                armor: member.armor,
+                resistances,
+                damage_bonus: 0.0,
+                abilities,
+                mana: ResourcePool::full(member.mana, member.mana_regen),
+                stamina: ResourcePool::full(member.stamina, member.stamina_regen),
+                spells,
+                effects: Vec::default(),
+                can_act: true,
+                speed: member.speed,
+                row: member.row.unwrap_or_default(),
+                stance: None,
+                escaped: false,
+            });
+            names.push(member.name);
+        }
+
+        let party = Party {
+            id,
+            members,
+            can_retreat: self.can_retreat,
+            retreated: false,
+            alliance,
+            escape_chance: self.escape_chance,
+            personality: self.personality.map(|personality| match personality {
+                PersonalityDefinition::Standard => Personality::default(),
+                PersonalityDefinition::Aggressive => Personality::aggressive(),
+                PersonalityDefinition::Cautious => Personality::cautious(),
+            }),
+        };
+
+        Ok((party, names))
+    }
+}
+
+impl WeaponDefinition {
+    /// Validates the definition and builds the weapon from the catalog weapon it refers to.
+    ///
+    /// ## Arguments
+    /// * `field` - The path to the weapon definition, used in error messages.
+    /// * `catalog` - The weapons the definition may refer to.
+    fn build(mut self, field: &str, catalog: &WeaponCatalog) -> Result<Weapon, ScenarioError> {
+        let base = match self.base.take() {
+            Some(base) => base,
+            None => {
+                return Err(ScenarioError::invalid(
+                    format!("{field}.type"),
+                    "the weapon type is required",
+                ))
+            }
+        };
+
+        match catalog.get(&base) {
+            Some(weapon) => self.apply(weapon.clone(), field),
+            None => Err(ScenarioError::invalid(
+                format!("{field}.type"),
+                &format!("there is no weapon `{base}` in the catalog"),
+            )),
+        }
+    }
+
+    /// Validates the definition and applies it to a weapon, replacing all values
+    /// that are defined.
+    ///
+    /// ## Arguments
+    /// * `weapon` - The weapon to start from.
+    /// * `field` - The path to the weapon definition, used in error messages.
+    pub(crate) fn apply(self, mut weapon: Weapon, field: &str) -> Result<Weapon, ScenarioError> {
+        if self.base.is_some() {
+            return Err(ScenarioError::invalid(
+                format!("{field}.type"),
+                "a catalog weapon cannot refer to another weapon",
+            ));
+        }
+
+        if let Some(name) = self.name {
+            weapon.name = name;
+        }
+
+        if let Some(damage) = self.damage {
+            if !damage.is_finite() || damage < 0.0 {
+                return Err(ScenarioError::invalid(
+                    format!("{field}.damage"),
+                    "damage must be a non-negative number",
+                ));
+            }
+            weapon.damage = damage;
+        }
+
+        if let Some(dice) = self.dice {
+            weapon.dice = Dice::parse(&dice).ok_or_else(|| {
+                ScenarioError::invalid(
+                    format!("{field}.dice"),
+                    "dice must be written as e.g. `1d6`",
+                )
+            })?;
+        }
+
+        weapon.damage_type = self.damage_type.unwrap_or(weapon.damage_type);
+        weapon.range = self.range.unwrap_or(weapon.range);
+        weapon.area = self.area.unwrap_or(weapon.area);
+
+        for (name, chance, target) in [
+            ("hit_chance", self.hit_chance, &mut weapon.hit_chance),
+            (
+                "critical_chance",
+                self.critical_chance,
+                &mut weapon.critical_chance,
+            ),
+        ] {
+            let Some(chance) = chance else {
+                continue;
+            };
+            if !(0.0..=1.0).contains(&chance) {
+                return Err(ScenarioError::invalid(
+                    format!("{field}.{name}"),
+                    "chances must be between 0 and 1",
+                ));
+            }
+            *target = chance;
+        }
+
+        if let Some(effects) = self.effects {
+            weapon.effects = Vec::with_capacity(effects.len());
+            for (index, effect) in effects.into_iter().enumerate() {
+                let field = format!("{field}.effects[{index}]");
+                match effect.build(&field)? {
+                    Action::StatusEffect(effect) => weapon.effects.push(effect),
+                    _ => {
+                        return Err(ScenarioError::invalid(
+                            format!("{field}.type"),
+                            "weapons can only apply status effects",
+                        ))
+                    }
+                }
+            }
+        }
+
+        if let Some(cost) = self.cost {
+            weapon.cost = cost.build(&format!("{field}.cost"))?;
+        }
+
+        Ok(weapon)
+    }
+}
+
+impl AbilityDefinition {
+    /// Validates the definition and builds the ability from it.
+    ///
+    /// ## Arguments
+    /// * `field` - The path to the ability definition, used in error messages.
+    fn build(self, field: &str) -> Result<Ability, ScenarioError> {
+        if let ActionDefinition::Attack { .. } = self.action {
+            return Err(ScenarioError::invalid(
+                format!("{field}.type"),
+                "only spells can attack; members attack with their weapon",
+            ));
+        }
+
+        Ok(Ability {
+            action: self.action.build(field)?,
+            cost: self.cost.build(&format!("{field}.cost"))?,
+        })
+    }
+}
+
+impl SpellDefinition {
+    /// Validates the definition and builds the spell from it.
+    ///
+    /// ## Arguments
+    /// * `field` - The path to the spell definition, used in error messages.
+    fn build(self, field: &str) -> Result<Spell, ScenarioError> {
+        if self.name.trim().is_empty() {
+            return Err(ScenarioError::invalid(
+                format!("{field}.name"),
+                "spells need a name",
+            ));
+        }
+
+        if let ActionDefinition::Defend { .. } | ActionDefinition::Guard = self.effect {
+            return Err(ScenarioError::invalid(
+                format!("{field}.effect.type"),
+                "stances are abilities rather than spells",
+            ));
+        }
+
+        let mut action = self.effect.build(&format!("{field}.effect"))?;
+        if let Action::SimpleAttack(SimpleAttackAction {
+            weapon: Some(weapon),
+            ..
+        }) = &mut action
+        {
+            // Spells are cast from a distance.
+            weapon.name = self.name.clone();
+            weapon.range = WeaponRange::Ranged;
+        }
+
+        Ok(Spell {
+            name: self.name,
+            action,
+            cost: self.cost.build(&format!("{field}.cost"))?,
+            area: self.target,
+            cooldown: self.cooldown,
+            ready_in: 0,
+        })
+    }
+}
+
+impl CostDefinition {
+    /// Validates the definition and builds the cost from it.
+    ///
+    /// ## Arguments
+    /// * `field` - The path to the cost definition, used in error messages.
+    fn build(self, field: &str) -> Result<Cost, ScenarioError> {
+        for (name, value) in [("mana", self.mana), ("stamina", self.stamina)] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(ScenarioError::invalid(
+                    format!("{field}.{name}"),
+                    "costs must be non-negative numbers",
+                ));
+            }
+        }
+
+        Ok(Cost {
+            mana: self.mana,
+            stamina: self.stamina,
+        })
+    }
+}
+
+impl ActionDefinition {
+    /// Validates the definition and builds the action from it.
+    ///
+    /// ## Arguments
+    /// * `field` - The path to the ability definition, used in error messages.
+    fn build(self, field: &str) -> Result<Action, ScenarioError> {
+        let (value, duration) = match self {
+            ActionDefinition::Heal { amount } => (Some(("amount", amount)), None),
+            ActionDefinition::Buff { damage_bonus } => (Some(("damage_bonus", damage_bonus)), None),
+            ActionDefinition::Poison { damage, duration } => {
+                (Some(("damage", damage)), Some(duration))
+            }
+            ActionDefinition::Regen { amount, duration } => {
+                (Some(("amount", amount)), Some(duration))
+            }
+            ActionDefinition::Stun { duration } | ActionDefinition::Sleep { duration } => {
+                (None, Some(duration))
+            }
+            ActionDefinition::Attack { damage, .. } => (Some(("damage", damage)), None),
+            ActionDefinition::Defend { damage_multiplier } => {
+                if !(0.0..=1.0).contains(&damage_multiplier) {
+                    return Err(ScenarioError::invalid(
+                        format!("{field}.damage_multiplier"),
+                        "the multiplier must be between 0 and 1",
+                    ));
+                }
+                (None, None)
+            }
+            ActionDefinition::Guard => (None, None),
+        };
+
+        if let Some((name, value)) = value {
+            if !value.is_finite() || value <= 0.0 {
+                return Err(ScenarioError::invalid(
+                    format!("{field}.{name}"),
+                    "the value must be a positive number",
+                ));
+            }
+        }
+
+        if duration == Some(0) {
+            return Err(ScenarioError::invalid(
+                format!("{field}.duration"),
+                "effects must last at least one turn",
+            ));
+        }
+
+        let effect = |kind, duration| Action::StatusEffect(StatusEffect { kind, duration });
+        Ok(match self {
+            ActionDefinition::Heal { amount } => Action::Heal(HealAction { amount }),
+            ActionDefinition::Buff { damage_bonus } => Action::Buff(BuffAction { damage_bonus }),
+            ActionDefinition::Poison { damage, duration } => {
+                effect(StatusEffectKind::Poison { damage }, duration)
+            }
+            ActionDefinition::Regen { amount, duration } => {
+                effect(StatusEffectKind::Regen { amount }, duration)
+            }
+            ActionDefinition::Stun { duration } => effect(StatusEffectKind::Stun, duration),
+            ActionDefinition::Sleep { duration } => effect(StatusEffectKind::Sleep, duration),
+            ActionDefinition::Attack {
+                damage,
+                damage_type,
+            } => Action::SimpleAttack(SimpleAttackAction {
+                weapon: Some(Weapon {
+                    damage,
+                    damage_type,
+                    ..Default::default()
+                }),
+                damage,
+                roll: AttackRoll::Hit,
+            }),
+            ActionDefinition::Defend { damage_multiplier } => {
+                Action::Defend(DefendAction { damage_multiplier })
+            }
+            ActionDefinition::Guard => Action::Guard,
+        })
+    }
+}
+
+impl ScenarioError {
+    /// Creates an error describing an invalid field value.
+    fn invalid(field: String, message: &str) -> Self {
+        Self::Invalid {
+            field,
+            message: message.to_string(),
+        }
+    }
+
+    /// Creates an error from a parser error tracking the offending field.
+    fn from_parse<E: Display>(error: serde_path_to_error::Error<E>) -> Self {
+        Self::Parse {
+            field: error.path().to_string(),
+            message: error.inner().to_string(),
+        }
+    }
+}
+
+impl Display for ScenarioError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScenarioError::Io(error) => write!(f, "unable to read file: {error}"),
+            ScenarioError::UnsupportedFormat(path) => write!(
+                f,
+                "unsupported file format for {path}; expected a .toml or .json file"
+            ),
+            ScenarioError::Parse { field, message } => {
+                write!(f, "unable to parse scenario at `{field}`: {message}")
+            }
+            ScenarioError::Invalid { field, message } => {
+                write!(f, "invalid scenario value at `{field}`: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ScenarioError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENARIO: &str = r#"
+        [initiator]
+        can_retreat = true
+
+        [[initiator.members]]
+        name = "Brull"
+        health = 20
+        weapon = { type = "fists", damage = 10 }
+
+        [opponent]
+
+        [[opponent.members]]
+        health = 15
+        weapon = { type = "stick", damage = 5 }
+    "#;
+
+    #[test]
+    fn toml_scenario_works() {
+        let scenario = Scenario::from_toml(SCENARIO, &WeaponCatalog::builtin()).unwrap();
+        assert!(scenario.conflict.parties[0].can_retreat);
+        assert!(!scenario.conflict.parties[1].can_retreat);
+        assert_eq!(scenario.conflict.parties[1].id, 1);
+        assert_eq!(
+            scenario.conflict.parties[1].members[0].weapon,
+            Weapon {
+                name: "a stick".to_string(),
+                damage: 5.0,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            scenario.names,
+            vec![vec![Some("Brull".to_string())], vec![None]]
+        );
+    }
+
+    #[test]
+    fn json_scenario_works() {
+        let scenario = Scenario::from_json(
+            r#"{
+                "initiator": { "members": [{ "health": 20, "weapon": { "type": "fists", "damage": 10 } }] },
+                "opponent": { "can_retreat": true, "members": [{ "health": 15, "weapon": { "type": "stick", "damage": 5 } }] }
+            }"#,
+            &WeaponCatalog::builtin(),
+        )
+        .unwrap();
+        assert!(scenario.conflict.parties[1].can_retreat);
+        assert_eq!(scenario.conflict.parties[0].members[0].health, 20.0);
+    }
+
+    #[test]
+    fn parse_errors_name_the_field() {
+        let text = SCENARIO.replace("damage = 5 }", "damage = \"five\" }");
+        match Scenario::from_toml(&text, &WeaponCatalog::builtin()) {
+            Err(ScenarioError::Parse { field, .. }) => {
+                assert_eq!(field, "opponent.members[0].weapon.damage")
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn weapons_refer_to_the_catalog() {
+        let catalog = WeaponCatalog::builtin();
+        let text = SCENARIO.replace("{ type = \"stick\", damage = 5 }", "{ type = \"sword\" }");
+        let scenario = Scenario::from_toml(&text, &catalog).unwrap();
+        assert_eq!(
+            &scenario.conflict.parties[1].members[0].weapon,
+            catalog.get("sword").unwrap()
+        );
+
+        let text = SCENARIO.replace("\"stick\"", "\"spoon\"");
+        match Scenario::from_toml(&text, &catalog) {
+            Err(ScenarioError::Invalid { field, .. }) => {
+                assert_eq!(field, "opponent.members[0].weapon.type")
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn invalid_values_name_the_field() {
+        let text = SCENARIO.replace("health = 15", "health = -1");
+        match Scenario::from_toml(&text, &WeaponCatalog::builtin()) {
+            Err(ScenarioError::Invalid { field, .. }) => {
+                assert_eq!(field, "opponent.members[0].health")
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn stochastic_weapons_work() {
+        let text = SCENARIO.replace(
+            "damage = 5 }",
+            "damage = 5, dice = \"2d4\", hit_chance = 0.75, critical_chance = 0.1 }",
+        );
+        let scenario = Scenario::from_toml(&text, &WeaponCatalog::builtin()).unwrap();
+        assert_eq!(
+            scenario.conflict.parties[1].members[0].weapon,
+            Weapon {
+                name: "a stick".to_string(),
+                damage: 5.0,
+                dice: Dice { count: 2, sides: 4 },
+                hit_chance: 0.75,
+                critical_chance: 0.1,
+                ..Default::default()
+            }
+        );
+
+        let text = SCENARIO.replace("damage = 5 }", "damage = 5, dice = \"d4\" }");
+        match Scenario::from_toml(&text, &WeaponCatalog::builtin()) {
+            Err(ScenarioError::Invalid { field, .. }) => {
+                assert_eq!(field, "opponent.members[0].weapon.dice")
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+
+        let text = SCENARIO.replace("damage = 5 }", "damage = 5, hit_chance = 2 }");
+        match Scenario::from_toml(&text, &WeaponCatalog::builtin()) {
+            Err(ScenarioError::Invalid { field, .. }) => {
+                assert_eq!(field, "opponent.members[0].weapon.hit_chance")
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn armor_and_resistances_work() {
+        let text = SCENARIO.replace(
+            "damage = 5 }",
+            "damage = 5 }\n        armor = 2\n        resistances = { fire = 0.5, cold = 2 }",
+        );
+        let scenario = Scenario::from_toml(&text, &WeaponCatalog::builtin()).unwrap();
+        let member = &scenario.conflict.parties[1].members[0];
+        assert_eq!(member.armor, 2.0);
+        assert_eq!(member.damage_multiplier(DamageType::Fire), 0.5);
+        assert_eq!(member.damage_multiplier(DamageType::Cold), 2.0);
+        assert_eq!(member.damage_multiplier(DamageType::Slashing), 1.0);
+
+        let text = SCENARIO.replace(
+            "damage = 5 }",
+            "damage = 5 }\n        resistances = { fire = -1 }",
+        );
+        match Scenario::from_toml(&text, &WeaponCatalog::builtin()) {
+            Err(ScenarioError::Invalid { field, .. }) => {
+                assert_eq!(field, "opponent.members[0].resistances.fire")
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn further_parties_and_alliances_work() {
+        let text = format!(
+            "{SCENARIO}
+        [[others]]
+        alliance = \"village\"
+
+        [[others.members]]
+        health = 10
+        weapon = {{ type = \"stick\" }}
+
+        [[others]]
+
+        [[others.members]]
+        health = 12
+        weapon = {{ type = \"fists\" }}
+        "
+        )
+        .replace("[initiator]", "[initiator]\n        alliance = \"village\"");
+        let scenario = Scenario::from_toml(&text, &WeaponCatalog::builtin()).unwrap();
+        let alliances: Vec<_> = scenario
+            .conflict
+            .parties
+            .iter()
+            .map(|p| p.alliance)
+            .collect();
+        assert_eq!(alliances, vec![0, 1, 0, 3]);
+        assert_eq!(scenario.names.len(), 4);
+
+        let text = text.replace("[opponent]", "[opponent]\n        alliance = \"village\"");
+        match Scenario::from_toml(&text, &WeaponCatalog::builtin()) {
+            Err(ScenarioError::Invalid { field, .. }) => assert_eq!(field, "opponent.alliance"),
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn rows_enable_formations() {
+        let scenario = Scenario::from_toml(SCENARIO, &WeaponCatalog::builtin()).unwrap();
+        assert!(!scenario.conflict.formations);
+
+        let text = SCENARIO.replace("damage = 5 }", "damage = 5 }\n        row = \"back\"");
+        let scenario = Scenario::from_toml(&text, &WeaponCatalog::builtin()).unwrap();
+        assert!(scenario.conflict.formations);
+        assert_eq!(scenario.conflict.parties[1].members[0].row, Row::Back);
+        assert_eq!(scenario.conflict.parties[0].members[0].row, Row::Front);
+    }
+
+    #[test]
+    fn resources_and_costs_work() {
+        let text = SCENARIO.replace(
+            "damage = 10 }",
+            "damage = 10, cost = { stamina = 2 } }\n        stamina = 4\n        mana = 6\n        mana_regen = 1\n        abilities = [{ type = \"heal\", amount = 5, cost = { mana = 3 } }]",
+        );
+        let scenario = Scenario::from_toml(&text, &WeaponCatalog::builtin()).unwrap();
+        let member = &scenario.conflict.parties[0].members[0];
+        assert_eq!(member.mana, ResourcePool::full(6.0, 1.0));
+        assert_eq!(member.stamina, ResourcePool::full(4.0, 0.0));
+        assert_eq!(member.weapon.cost.stamina, 2.0);
+        assert_eq!(
+            member.abilities[0].cost,
+            Cost {
+                mana: 3.0,
+                stamina: 0.0
+            }
+        );
+
+        let text = SCENARIO.replace(
+            "damage = 10 }",
+            "damage = 10 }\n        abilities = [{ type = \"heal\", amount = 5, cost = { mana = -1 } }]",
+        );
+        match Scenario::from_toml(&text, &WeaponCatalog::builtin()) {
+            Err(ScenarioError::Invalid { field, .. }) => {
+                assert_eq!(field, "initiator.members[0].abilities[0].cost.mana")
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+
+        let text = SCENARIO.replace(
+            "damage = 10 }",
+            "damage = 10 }\n        abilities = [{ type = \"heal\", amount = 5, range = 3 }]",
+        );
+        assert!(Scenario::from_toml(&text, &WeaponCatalog::builtin()).is_err());
+    }
+
+    #[test]
+    fn spells_work() {
+        let text = SCENARIO.replace(
+            "damage = 10 }",
+            "damage = 10 }\n        spells = [{ name = \"Frost\", effect = { type = \"attack\", damage = 4, damage_type = \"cold\" }, target = \"all\", cooldown = 2 }]",
+        );
+        let scenario = Scenario::from_toml(&text, &WeaponCatalog::builtin()).unwrap();
+        let spell = &scenario.conflict.parties[0].members[0].spells[0];
+        assert_eq!(spell.name, "Frost");
+        assert_eq!(
+            (spell.area, spell.cooldown, spell.ready_in),
+            (WeaponArea::All, 2, 0)
+        );
+        assert_eq!(spell.action.max_damage(), 4.0);
+
+        let text = SCENARIO.replace(
+            "damage = 10 }",
+            "damage = 10 }\n        abilities = [{ type = \"attack\", damage = 4 }]",
+        );
+        match Scenario::from_toml(&text, &WeaponCatalog::builtin()) {
+            Err(ScenarioError::Invalid { field, .. }) => {
+                assert_eq!(field, "initiator.members[0].abilities[0].type")
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn abilities_work() {
+        let text = SCENARIO.replace(
+            "damage = 10 }",
+            "damage = 10 }\n        max_health = 25\n        abilities = [{ type = \"heal\", amount = 5 }]",
+        );
+        let scenario = Scenario::from_toml(&text, &WeaponCatalog::builtin()).unwrap();
+        let member = &scenario.conflict.parties[0].members[0];
+        assert_eq!(member.max_health, 25.0);
+        assert_eq!(
+            member.abilities,
+            vec![Action::Heal(HealAction { amount: 5.0 }).into()]
+        );
+
+        let text = SCENARIO.replace(
+            "damage = 10 }",
+            "damage = 10 }\n        abilities = [{ type = \"stun\", duration = 2 }]",
+        );
+        let scenario = Scenario::from_toml(&text, &WeaponCatalog::builtin()).unwrap();
+        assert_eq!(
+            scenario.conflict.parties[0].members[0].abilities,
+            vec![Action::StatusEffect(StatusEffect {
+                kind: StatusEffectKind::Stun,
+                duration: 2
+            })
+            .into()]
+        );
+
+        let text = SCENARIO.replace(
+            "damage = 10 }",
+            "damage = 10 }\n        abilities = [{ type = \"poison\", damage = 2, duration = 0 }]",
+        );
+        match Scenario::from_toml(&text, &WeaponCatalog::builtin()) {
+            Err(ScenarioError::Invalid { field, .. }) => {
+                assert_eq!(field, "initiator.members[0].abilities[0].duration")
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+
+        let text = SCENARIO.replace(
+            "damage = 10 }",
+            "damage = 10 }\n        abilities = [{ type = \"buff\", damage_bonus = 0 }]",
+        );
+        match Scenario::from_toml(&text, &WeaponCatalog::builtin()) {
+            Err(ScenarioError::Invalid { field, .. }) => {
+                assert_eq!(field, "initiator.members[0].abilities[0].damage_bonus")
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn escape_chance_works() {
+        let text = SCENARIO.replace(
+            "can_retreat = true",
+            "can_retreat = true\n        escape_chance = 0.5",
+        );
+        let scenario = Scenario::from_toml(&text, &WeaponCatalog::builtin()).unwrap();
+        assert_eq!(scenario.conflict.parties[0].escape_chance, Some(0.5));
+        assert_eq!(scenario.conflict.parties[1].escape_chance, None);
+
+        for (from, to) in [
+            ("can_retreat = true", "escape_chance = 0.5"),
+            (
+                "can_retreat = true",
+                "can_retreat = true\n        escape_chance = 0",
+            ),
+        ] {
+            match Scenario::from_toml(&SCENARIO.replace(from, to), &WeaponCatalog::builtin()) {
+                Err(ScenarioError::Invalid { field, .. }) => {
+                    assert_eq!(field, "initiator.escape_chance")
+                }
+                other => panic!("unexpected result: {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn stances_work() {
+        let text = SCENARIO.replace(
+            "damage = 10 }",
+            "damage = 10 }\n        abilities = [{ type = \"defend\", damage_multiplier = 0.5 }, { type = \"guard\" }]",
+        );
+        let scenario = Scenario::from_toml(&text, &WeaponCatalog::builtin()).unwrap();
+        assert_eq!(
+            scenario.conflict.parties[0].members[0].abilities,
+            vec![
+                Action::Defend(DefendAction {
+                    damage_multiplier: 0.5
+                })
+                .into(),
+                Action::Guard.into()
+            ]
+        );
+
+        let text = SCENARIO.replace(
+            "damage = 10 }",
+            "damage = 10 }\n        abilities = [{ type = \"defend\", damage_multiplier = 2 }]",
+        );
+        match Scenario::from_toml(&text, &WeaponCatalog::builtin()) {
+            Err(ScenarioError::Invalid { field, .. }) => {
+                assert_eq!(field, "initiator.members[0].abilities[0].damage_multiplier")
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+
+        let text = SCENARIO.replace(
+            "damage = 10 }",
+            "damage = 10 }\n        spells = [{ name = \"Ward\", effect = { type = \"guard\" } }]",
+        );
+        match Scenario::from_toml(&text, &WeaponCatalog::builtin()) {
+            Err(ScenarioError::Invalid { field, .. }) => {
+                assert_eq!(field, "initiator.members[0].spells[0].effect.type")
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn personalities_work() {
+        let text = SCENARIO.replace(
+            "[opponent]",
+            "[opponent]\n        personality = \"cautious\"",
+        );
+        let scenario = Scenario::from_toml(&text, &WeaponCatalog::builtin()).unwrap();
+        assert_eq!(scenario.conflict.parties[0].personality, None);
+        assert_eq!(
+            scenario.conflict.parties[1].personality,
+            Some(Personality::cautious())
+        );
+
+        let text = SCENARIO.replace("[opponent]", "[opponent]\n        personality = \"brave\"");
+        assert!(Scenario::from_toml(&text, &WeaponCatalog::builtin()).is_err());
+    }
+}
@@ -1,23 +1,78 @@
-use crate::action::{AppliedAction, TargetedAction};
-use crate::action_iterator::ActionIterator;
+use crate::action::AppliedAction;
 use crate::conflict::Conflict;
-use crate::party::Participant;
-use crate::utility_value::get_utility;
+use crate::max_n::MaxN;
+use crate::monte_carlo::MonteCarlo;
+use crate::move_ordering::{MoveOrdering, OrderedActions};
+use crate::transposition::{Bound, TranspositionEntry, TranspositionTable};
+use crate::utility_value::{Evaluator, UtilityBounds};
 use crate::value::{Cutoff, TerminalState, Value};
 use log::trace;
+use serde::{Serialize, Serializer};
 use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
 use std::time::{Duration, Instant};
 
 pub struct Solver;
 
+/// The number of node evaluations between checks of the search deadline.
+pub(crate) const DEADLINE_CHECK_INTERVAL: usize = 1024;
+
 /// The strategy to use with the solver.
 #[derive(Debug, Copy, Clone)]
-#[allow(dead_code)]
 pub enum SolverStrategy {
     /// Use a simple depth-limited search.
     DepthLimited(usize),
     /// Use iterative deepening.
     IterativeDeepening(usize),
+    /// Use iterative deepening until the time budget is spent. The iteration in progress
+    /// when time runs out is aborted and the result of the deepest complete iteration is
+    /// returned. The first iteration always completes.
+    TimeLimited(Duration),
+    /// Use Monte Carlo Tree Search; scales to larger conflicts at the cost of exactness.
+    MonteCarlo {
+        /// The number of random playouts to perform.
+        iterations: usize,
+        /// The exploration constant of UCT; `√2` is a common choice.
+        exploration: f32,
+    },
+}
+
+impl SolverStrategy {
+    /// Divides the strategy among several searches one level deeper in the tree,
+    /// e.g. one for each available action.
+    fn split(self, searches: usize) -> Self {
+        let strategy = match self {
+            SolverStrategy::DepthLimited(max_depth) => {
+                SolverStrategy::DepthLimited(max_depth.saturating_sub(1).max(1))
+            }
+            SolverStrategy::IterativeDeepening(max_depth) => {
+                SolverStrategy::IterativeDeepening(max_depth.saturating_sub(1).max(1))
+            }
+            strategy => strategy,
+        };
+        strategy.share(searches)
+    }
+
+    /// Divides the time budget or playouts of the strategy among several searches
+    /// at the same level of the tree, e.g. one for each result of a random action.
+    fn share(self, searches: usize) -> Self {
+        let searches = searches.max(1);
+        match self {
+            SolverStrategy::DepthLimited(_) | SolverStrategy::IterativeDeepening(_) => self,
+            SolverStrategy::TimeLimited(budget) => {
+                SolverStrategy::TimeLimited(budget / searches as u32)
+            }
+            SolverStrategy::MonteCarlo {
+                iterations,
+                exploration,
+            } => SolverStrategy::MonteCarlo {
+                iterations: (iterations / searches).max(1),
+                exploration,
+            },
+        }
+    }
 }
 
 impl Solver {
@@ -26,43 +81,487 @@ impl Solver {
     ///
     /// ## Arguments
     /// * `conflict` - The conflict situation to resolve.
-    /// * `max_depth` - The maximum search depth.
+    /// * `strategy` - The search algorithm to use.
+    /// * `evaluator` - Scores the states of the conflict in the eyes of the initiating party,
+    ///   e.g. [`Personality::default`](crate::utility_value::Personality::default).
+    ///   Parties with a [`personality`](crate::party::Party::personality) of their own
+    ///   use that instead.
+    ///
+    /// ## Returns
+    /// The [`Outcome`] of the conflict.
+    pub fn engage(
+        conflict: &Conflict,
+        strategy: SolverStrategy,
+        evaluator: &dyn Evaluator,
+    ) -> Outcome {
+        Self::engage_parallel(conflict, strategy, evaluator, 1)
+    }
+
+    /// Predicts the sequence of optimal moves to resolve the conflict like
+    /// [`Solver::engage`], sharing the work of the alpha-beta search among several threads.
+    ///
+    /// The first action at the root of every iteration is searched on its own; its value
+    /// then narrows the search window for the remaining actions, which the threads take
+    /// on one at a time (Young Brothers Wait). The outcome is the same as that of the
+    /// sequential search, although the timeline may pick a different one of several
+    /// equally good actions and the statistics add up the work of all threads.
+    /// Monte Carlo Tree Search and conflicts in which parties follow preferences of
+    /// their own are always searched by a single thread.
+    ///
+    /// ## Arguments
+    /// * `conflict` - The conflict situation to resolve.
+    /// * `strategy` - The search algorithm to use.
+    /// * `evaluator` - Scores the states of the conflict, see [`Solver::engage`].
+    /// * `threads` - The number of threads to search with; at least one.
     ///
     /// ## Returns
     /// The [`Outcome`] of the conflict.
-    pub fn engage(conflict: &Conflict, strategy: SolverStrategy) -> Outcome {
+    pub fn engage_parallel(
+        conflict: &Conflict,
+        strategy: SolverStrategy,
+        evaluator: &dyn Evaluator,
+        threads: usize,
+    ) -> Outcome {
+        let evaluator = match &conflict.initiator().personality {
+            Some(personality) => personality,
+            None => evaluator,
+        };
+
+        // The tree search always assumes opponents play against the initiating party.
+        if !Self::is_zero_sum(conflict) {
+            match strategy {
+                SolverStrategy::DepthLimited(max_depth) => {
+                    return MaxN::search(conflict, max_depth, max_depth, None, evaluator);
+                }
+                SolverStrategy::IterativeDeepening(max_depth) => {
+                    return MaxN::search(conflict, 1, max_depth, None, evaluator);
+                }
+                SolverStrategy::TimeLimited(budget) => {
+                    let deadline = Instant::now() + budget;
+                    return MaxN::search(conflict, 1, usize::MAX, Some(deadline), evaluator);
+                }
+                SolverStrategy::MonteCarlo { .. } => {}
+            }
+        }
+
+        // The table is shared by all iterations of iterative deepening
+        // so that later iterations can reuse results of earlier ones,
+        // as well as by all threads.
+        let table = TranspositionTable::default();
+        let threads = threads.max(1);
+
+        // Killer moves and the history of cutoffs carry over likewise.
+        let mut ordering = MoveOrdering::default();
+
         match strategy {
             SolverStrategy::DepthLimited(max_depth) => {
                 let max_depth = max_depth.max(1);
-                Self::minimax(conflict, max_depth)
+                Self::minimax_parallel(
+                    conflict,
+                    max_depth,
+                    &table,
+                    None,
+                    evaluator,
+                    threads,
+                    &mut ordering,
+                )
+                .expect("A search without deadline always completes")
             }
-            SolverStrategy::IterativeDeepening(max_depth) => {
-                let max_depth = max_depth.max(1);
-                let mut depth = 1;
-                loop {
-                    log_increase_search_depth_to(depth, max_depth);
-                    let outcome = Self::minimax(conflict, depth);
-                    let should_stop = match outcome.outcome {
-                        OutcomeType::Win(_) => true,
-                        OutcomeType::Lose(_) => false,
-                        OutcomeType::Remain(_) => false, // we may want to accept retreats too
-                        OutcomeType::Retreat(_) => false,
-                        OutcomeType::Unknown(_) => false,
-                    };
+            SolverStrategy::IterativeDeepening(max_depth) => Self::deepen(
+                conflict,
+                max_depth.max(1),
+                None,
+                &table,
+                evaluator,
+                threads,
+                &mut ordering,
+            ),
+            SolverStrategy::TimeLimited(budget) => {
+                let start_time = Instant::now();
+                let deadline = start_time + budget;
+                let mut outcome = Self::deepen(
+                    conflict,
+                    usize::MAX,
+                    Some(deadline),
+                    &table,
+                    evaluator,
+                    threads,
+                    &mut ordering,
+                );
+                outcome.search_duration = Instant::now() - start_time;
+                outcome
+            }
+            SolverStrategy::MonteCarlo {
+                iterations,
+                exploration,
+            } => MonteCarlo::search(conflict, iterations.max(1), exploration, evaluator),
+        }
+    }
 
-                    if !outcome.depth_limited || should_stop {
-                        log_finish_iddfs(depth);
-                        return outcome;
-                    }
+    /// Evaluates every action available to the acting party in the current state, so that
+    /// a game can take a single action, update its state and ask again.
+    ///
+    /// ## Arguments
+    /// * `conflict` - The current state of the conflict. Whether the conflict has already
+    ///   ended is up to the caller, see [`Conflict::has_ended_after`].
+    /// * `strategy` - The search algorithm used after each action. Limits apply to the
+    ///   advice as a whole: every action leaves one level less to search, and time
+    ///   budgets and playouts are split evenly among the actions and their results.
+    /// * `evaluator` - Scores the states of the conflict in the eyes of the initiating party,
+    ///   see [`Solver::engage`].
+    ///
+    /// ## Returns
+    /// The applicable actions, best first for the acting party: the initiating party and
+    /// its allies prefer high scores, everyone else low scores. Once parties follow
+    /// preferences of their own, the acting party ranks the actions by those instead.
+    /// Actions of equal rank keep the order in which they were generated.
+    pub fn advise(
+        conflict: &Conflict,
+        strategy: SolverStrategy,
+        evaluator: &dyn Evaluator,
+    ) -> Vec<Advice> {
+        let evaluator = match &conflict.initiator().personality {
+            Some(personality) => personality,
+            None => evaluator,
+        };
 
-                    depth += 1;
-                    if depth > max_depth {
-                        log_stop_iddfs(max_depth);
-                        return outcome;
-                    }
+        let party_id = conflict.acting_party().id;
+        let actions: Vec<_> = conflict
+            .actions()
+            .into_iter()
+            .flatten()
+            .filter(|action| conflict.apply(party_id, action).is_some())
+            .collect();
+
+        let strategy = strategy.split(actions.len());
+        let mut advice: Vec<_> = actions
+            .into_iter()
+            .map(|action| Self::evaluate_action(conflict, action, strategy, evaluator))
+            .collect();
+
+        advice.sort_by(|a, b| b.utility.total_cmp(&a.utility));
+        advice
+    }
+
+    /// Determines whether every party other than the initiating one plays against
+    /// it, or for it if allied. Once any other party follows preferences of its own,
+    /// the conflict is no longer zero-sum, and every party plays for itself.
+    fn is_zero_sum(conflict: &Conflict) -> bool {
+        conflict.parties[1..]
+            .iter()
+            .all(|party| party.personality.is_none())
+    }
+
+    /// Evaluates a single action by searching the state after each of its results.
+    fn evaluate_action(
+        conflict: &Conflict,
+        action: AppliedAction,
+        strategy: SolverStrategy,
+        evaluator: &dyn Evaluator,
+    ) -> Advice {
+        let party_id = conflict.acting_party().id;
+        let outcomes = action.outcomes();
+        let most_likely = AppliedAction::most_likely(&outcomes);
+        let strategy = strategy.share(outcomes.len());
+        let is_zero_sum = Self::is_zero_sum(conflict);
+
+        let mut advice = Advice {
+            action,
+            outcome: OutcomeType::Expected(0.0),
+            win_probability: 0.0,
+            utility: 0.0,
+            principal_variation: Vec::default(),
+        };
+
+        let mut expected = 0.0;
+        for (index, (probability, result)) in outcomes.iter().enumerate() {
+            let Some(state) = conflict.apply(party_id, result) else {
+                continue;
+            };
+
+            let event = Event {
+                turn: 1,
+                party_id,
+                is_initiator_turn: conflict.is_initiator_turn(),
+                action: result.clone(),
+                probability: *probability,
+                depth: 1,
+                state: state.clone(),
+            };
+
+            // A conflict that ended with this action leaves nothing to search.
+            let (outcome, win_probability, timeline) = if state.has_ended_after(Some(result)) {
+                let value = evaluator.utility(&state, state.initiator().id);
+                (
+                    OutcomeType::from(value),
+                    value.win_probability(),
+                    Vec::default(),
+                )
+            } else {
+                let outcome = Self::engage(&state, strategy, evaluator);
+                (outcome.outcome, outcome.win_probability, outcome.timeline)
+            };
+
+            // Parties with preferences of their own judge where the conflict is headed.
+            let utility = if !is_zero_sum {
+                let end = timeline.last().map_or(&state, |event| &event.state);
+                MaxN::utility(end, party_id, evaluator)
+            } else if conflict.is_initiator_turn() {
+                outcome.score()
+            } else {
+                -outcome.score()
+            };
+
+            expected += probability * outcome.score();
+            advice.win_probability += probability * win_probability;
+            advice.utility += probability * utility;
+            if index == most_likely {
+                advice.outcome = outcome;
+                advice.principal_variation = std::iter::once(event)
+                    .chain(timeline.into_iter().map(|event| Event {
+                        turn: event.turn + 1,
+                        depth: event.depth + 1,
+                        ..event
+                    }))
+                    .collect();
+            }
+        }
+
+        if outcomes.len() > 1 {
+            advice.outcome = OutcomeType::Expected(expected);
+        }
+
+        advice
+    }
+
+    /// Runs depth-limited searches of increasing depth until the conflict
+    /// is resolved, the maximum depth is reached or time runs out.
+    ///
+    /// ## Arguments
+    /// * `conflict` - The conflict situation to resolve.
+    /// * `max_depth` - The maximum search depth.
+    /// * `deadline` - The point in time at which to abort the search, if any.
+    /// * `table` - The transposition table shared by all iterations.
+    /// * `evaluator` - Scores the states of the conflict.
+    /// * `threads` - The number of threads to search with.
+    /// * `ordering` - Orders the actions of every state, shared by all iterations.
+    ///
+    /// ## Returns
+    /// The [`Outcome`] of the deepest complete iteration.
+    fn deepen(
+        conflict: &Conflict,
+        max_depth: usize,
+        deadline: Option<Instant>,
+        table: &TranspositionTable,
+        evaluator: &dyn Evaluator,
+        threads: usize,
+        ordering: &mut MoveOrdering,
+    ) -> Outcome {
+        let mut depth = 1;
+        let mut best: Option<Outcome> = None;
+        loop {
+            log_increase_search_depth_to(depth, max_depth);
+
+            // The first iteration always completes so that there is a result to return.
+            let deadline = deadline.filter(|_| best.is_some());
+            let outcome = match deadline {
+                Some(deadline) if Instant::now() >= deadline => None,
+                _ => Self::minimax_parallel(
+                    conflict, depth, table, deadline, evaluator, threads, ordering,
+                ),
+            };
+
+            let outcome = match outcome {
+                Some(outcome) => outcome,
+                None => {
+                    log_search_timed_out(depth);
+                    return best.expect("The first iteration always completes");
                 }
+            };
+
+            let should_stop = match outcome.outcome {
+                OutcomeType::Win(_) => true,
+                OutcomeType::Lose(_) => false,
+                OutcomeType::Remain(_) => false, // we may want to accept retreats too
+                OutcomeType::Retreat(_) => false,
+                OutcomeType::Unknown(_) => false,
+                OutcomeType::Expected(_) => false,
+            };
+
+            if !outcome.depth_limited || should_stop {
+                log_finish_iddfs(depth);
+                return outcome;
+            }
+
+            depth += 1;
+            if depth > max_depth {
+                log_stop_iddfs(max_depth);
+                return outcome;
+            }
+
+            ordering.follow(outcome.timeline.iter().map(|event| event.action.clone()));
+            best = Some(outcome);
+        }
+    }
+
+    /// Searches the conflict to a fixed depth, sharing the work among several threads.
+    ///
+    /// ## Arguments
+    /// * `conflict` - The conflict situation to resolve.
+    /// * `max_depth` - The maximum search depth in the tree.
+    /// * `table` - The transposition table to consult and update, shared by all threads.
+    /// * `deadline` - The point in time at which to abort the search, if any.
+    /// * `evaluator` - Scores the states of the conflict.
+    /// * `threads` - The number of threads to search with.
+    /// * `ordering` - Orders the actions of every state; the threads sharing the work
+    ///   each start from a copy.
+    ///
+    /// ## Returns
+    /// The [`Outcome`] of the conflict, or [`None`] if the search was aborted.
+    fn minimax_parallel(
+        conflict: &Conflict,
+        max_depth: usize,
+        table: &TranspositionTable,
+        deadline: Option<Instant>,
+        evaluator: &dyn Evaluator,
+        threads: usize,
+        ordering: &mut MoveOrdering,
+    ) -> Option<Outcome> {
+        let start_time = Instant::now();
+        let is_maximizing = conflict.is_initiator_turn();
+        let party_id = conflict.acting_party().id;
+
+        // The root actions in the order of the sequential search.
+        let hash_action = table
+            .probe(conflict, is_maximizing)
+            .and_then(|entry| entry.best_action);
+        let actions: Vec<_> = match conflict.actions() {
+            Some(iter) => ordering
+                .order(conflict, iter, 0, hash_action.as_ref(), true)
+                .filter(|action| conflict.apply(party_id, action).is_some())
+                .collect(),
+            None => Vec::default(),
+        };
+
+        if threads < 2 || actions.len() < 2 {
+            return Self::minimax(
+                conflict, max_depth, table, deadline, evaluator, None, ordering,
+            );
+        }
+
+        // The eldest brother is searched on its own; its value bounds the search
+        // window of its younger brothers, which the threads take on one at a time.
+        let eldest = RootSplit::new(&actions[0], is_maximizing, None);
+        let eldest = Self::minimax(
+            conflict,
+            max_depth,
+            table,
+            deadline,
+            evaluator,
+            Some(&eldest),
+            ordering,
+        )?;
+
+        let bound = Mutex::new(eldest.outcome.score());
+        let next_index = AtomicUsize::new(1);
+        let ordering = &*ordering;
+        let searches = thread::scope(|scope| {
+            let threads: Vec<_> = (0..threads)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut ordering = ordering.clone();
+                        let mut searches = Vec::default();
+                        loop {
+                            let index = next_index.fetch_add(1, Ordering::Relaxed);
+                            let Some(action) = actions.get(index) else {
+                                return Some(searches);
+                            };
+
+                            let value = *bound.lock().expect("The bound is never poisoned");
+                            let split = RootSplit::new(action, is_maximizing, Some(value));
+                            let outcome = Self::minimax(
+                                conflict,
+                                max_depth,
+                                table,
+                                deadline,
+                                evaluator,
+                                Some(&split),
+                                &mut ordering,
+                            )?;
+
+                            // Only values within the search window are exact; the others
+                            // merely bound the true value of the action.
+                            let is_exact = split.contains(outcome.outcome.score());
+                            if is_exact {
+                                let mut bound = bound.lock().expect("The bound is never poisoned");
+                                *bound = if is_maximizing {
+                                    bound.max(outcome.outcome.score())
+                                } else {
+                                    bound.min(outcome.outcome.score())
+                                };
+                            }
+
+                            searches.push((index, is_exact, outcome));
+                        }
+                    })
+                })
+                .collect();
+
+            threads
+                .into_iter()
+                .map(|thread| thread.join().expect("Search threads do not panic"))
+                .collect::<Option<Vec<_>>>()
+        })?;
+
+        let mut searches: Vec<_> = searches.into_iter().flatten().collect();
+        searches.sort_by_key(|(index, ..)| *index);
+
+        let mut stats = Statistics::from(&eldest);
+        let mut best = (0, eldest);
+        for (index, is_exact, outcome) in searches {
+            stats.add(&outcome);
+
+            // On equal values, the first action wins.
+            let score = outcome.outcome.score();
+            let is_better = if is_maximizing {
+                score > best.1.outcome.score()
+            } else {
+                score < best.1.outcome.score()
+            };
+            if is_exact && is_better {
+                best = (index, outcome);
             }
         }
+
+        // The next iteration starts with the best action. Since the younger brothers
+        // were searched within narrower windows, their values may not bound that
+        // of the state.
+        let (index, best) = best;
+        table.store(
+            conflict,
+            is_maximizing,
+            TranspositionEntry {
+                draft: if stats.depth_limited {
+                    max_depth
+                } else {
+                    TranspositionEntry::UNLIMITED
+                },
+                value: TerminalState::from(best.outcome),
+                bound: Bound::Unbounded,
+                best_action: Some(actions[index].clone()),
+                win_probability: best.win_probability,
+            },
+        );
+
+        Some(Outcome {
+            evaluations: stats.evaluations,
+            cuts: stats.pruning_cuts,
+            transposition_hits: stats.transposition_hits,
+            max_visited_depth: stats.max_visited_depth,
+            search_duration: Instant::now() - start_time,
+            depth_limited: stats.depth_limited,
+            ..best
+        })
     }
 
     /// Uses the minimax algorithm to find the optimal outcome.
@@ -70,19 +569,46 @@ impl Solver {
     /// ## Arguments
     /// * `conflict` - The conflict situation to resolve.
     /// * `max_depth` - The maximum search depth in the tree. Can be used to limit search complexity.
+    /// * `table` - The transposition table to consult and update.
+    /// * `deadline` - The point in time at which to abort the search, if any.
+    /// * `evaluator` - Scores the states of the conflict.
+    /// * `split` - The share of the root actions to search, if the search is shared.
+    /// * `ordering` - Orders the actions of every state and learns from the cutoffs.
     ///
     /// ## Returns
-    /// The [`Outcome`] of the conflict.
-    fn minimax(conflict: &Conflict, max_depth: usize) -> Outcome {
-        // We start with a maximizing step, so the value is
-        // initialized to negative infinity.
-        let mut nodes = vec![Node::new_root(conflict.clone(), 0)];
+    /// The [`Outcome`] of the conflict, or [`None`] if the search was aborted.
+    fn minimax(
+        conflict: &Conflict,
+        max_depth: usize,
+        table: &TranspositionTable,
+        deadline: Option<Instant>,
+        evaluator: &dyn Evaluator,
+        split: Option<&RootSplit>,
+        ordering: &mut MoveOrdering,
+    ) -> Option<Outcome> {
+        // Unless the opponent has the initiative, we start with a maximizing
+        // step, so the value is initialized to negative infinity.
+        let mut root = Node::new_root(conflict.clone(), 0);
+        if let Some(split) = split {
+            root.value.alpha = split.alpha;
+            root.value.beta = split.beta;
+        }
+        let mut nodes = vec![root];
+
+        // The value of a root searching only some of its actions must not be mistaken
+        // for the value of the state.
+        let root_actions = split.map(|split| split.actions);
+        let may_store = |node: &Node| root_actions.is_none() || node.parent_id.is_some();
+
+        // Chance nodes are cut off using the range of possible utility values.
+        let bounds = evaluator.bounds(conflict, conflict.initiator().id, max_depth);
 
         let mut depth_limited = false;
 
         // Track expansion statistics.
         let mut evaluations = 0;
         let mut pruning_cuts = 0;
+        let mut transposition_hits = 0;
         let mut max_visited_depth = 0;
         let start_time = Instant::now();
 
@@ -90,6 +616,13 @@ impl Solver {
         'dfs: while let Some(id) = dfs_queue.pop() {
             evaluations += 1;
 
+            // Reading the clock is comparatively expensive, so it is only done occasionally.
+            if evaluations % DEADLINE_CHECK_INTERVAL == 0
+                && deadline.is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                return None;
+            }
+
             // The clone here is a hack to get around borrowing rules.
             let mut node = nodes[id].clone();
             log_exploring_node(&node);
@@ -97,24 +630,73 @@ impl Solver {
             // Track the deepest depths.
             max_visited_depth = max_visited_depth.max(node.depth);
 
+            // When visiting a node for the first time, the same state may already
+            // have been searched through a different order of actions or in
+            // an earlier iteration. The root node is always expanded since the
+            // timeline starts from its children. Chance nodes share the state of
+            // their parent and are never stored.
+            if node.action_iter.is_none() && node.chance.is_none() {
+                if let Some(entry) = table.probe(&node.state, node.is_maximizing) {
+                    let draft = max_depth - node.depth;
+                    let value = entry.cutoff(draft, node.value.alpha, node.value.beta);
+                    match value {
+                        Some(value) if node.parent_id.is_some() => {
+                            log_transposition_hit(&node, &value);
+                            transposition_hits += 1;
+                            *node.value = value;
+                            node.horizon_reached = entry.draft != TranspositionEntry::UNLIMITED;
+                            node.from_transposition = true;
+                            node.win_probability = entry.win_probability;
+                            depth_limited |= node.horizon_reached;
+
+                            Self::propagate_to_parent(&mut nodes, &node);
+                            nodes[id] = node;
+                            continue 'dfs;
+                        }
+                        _ => {
+                            // Try the best known action first to tighten the bounds early.
+                            node.hash_action = entry.best_action.clone();
+                        }
+                    }
+                }
+            }
+
             // Terminate iteration if the look-ahead depth is reached.
             // Note that we can only know if a state is terminal once we have
             // fully expanded it. This information is available further below,
             // after the node expansion step.
-            let continue_expansion = if node.is_maximizing && node.value.is_beta_cutoff() {
+            let continue_expansion = if let Some(chance) = &node.chance {
+                // Star1: stop once the outcomes explored so far determine that the
+                // expected value lies outside the search window.
+                match chance.cutoff(node.value.alpha, node.value.beta, &bounds) {
+                    Some(value) => {
+                        log_chance_cutoff(&node, &value);
+                        pruning_cuts += 1;
+                        *node.value = value;
+                        false
+                    }
+                    None => true,
+                }
+            } else if node.is_maximizing && node.value.is_beta_cutoff() {
                 log_beta_cutoff(&node);
                 pruning_cuts += 1;
+                Self::record_cutoff(ordering, &nodes, &node, max_depth);
+                if may_store(&node) {
+                    Self::store_transposition(table, &nodes, &node, max_depth, Bound::Lower);
+                }
                 false
             } else if !node.is_maximizing && node.value.is_alpha_cutoff() {
                 log_alpha_cutoff(&node);
                 pruning_cuts += 1;
-                false
-            } else if !node.is_maximizing && node.value.is_negative() {
-                log_minimizer_detected_defeat(&node);
-                pruning_cuts += 1;
+                Self::record_cutoff(ordering, &nodes, &node, max_depth);
+                if may_store(&node) {
+                    Self::store_transposition(table, &nodes, &node, max_depth, Bound::Upper);
+                }
                 false
             } else if node.depth == max_depth {
-                *node.value = get_utility(&node.state);
+                *node.value = evaluator.utility(&node.state, node.state.initiator().id);
+                node.win_probability = node.value.win_probability();
+                node.horizon_reached = true;
                 nodes[node.id].value = node.value.clone();
                 depth_limited = true;
                 log_max_search_depth_reached(&node);
@@ -124,23 +706,28 @@ impl Solver {
             };
 
             if !continue_expansion {
-                Self::propagate_to_parent(&mut nodes, &mut node);
+                Self::propagate_to_parent(&mut nodes, &node);
                 continue 'dfs;
             }
 
             // Expand the search tree at the current node.
-            let expansion_result = Self::minimax_expand(node, nodes.len());
+            let expansion_result =
+                Self::minimax_expand(node, nodes.len(), &bounds, root_actions, ordering);
 
             // Handle expansion or exhaustion of the node.
             let node = match expansion_result {
                 ExpansionResult::Exhausted(Exhaustion { mut node }) => {
-                    let value = if (*node.value).is_finite() {
+                    let value = if let Some(chance) = &node.chance {
+                        log_node_fully_explored(&node, &nodes);
+                        TerminalState::Expected(chance.expected)
+                    } else if (*node.value).is_finite() {
                         log_node_fully_explored(&node, &nodes);
                         *node.value
                     } else {
                         // If this is a terminal node we either have a winner or loser.
-                        let value = get_utility(&node.state);
+                        let value = evaluator.utility(&node.state, node.state.initiator().id);
                         log_node_terminal_state(&node, &value, &nodes);
+                        node.win_probability = value.win_probability();
                         value
                     };
 
@@ -148,8 +735,13 @@ impl Solver {
                     node.value.value = value;
                     nodes[id].value = node.value.clone();
 
+                    if node.chance.is_none() && may_store(&node) {
+                        let bound = node.window_bound();
+                        Self::store_transposition(table, &nodes, &node, max_depth, bound);
+                    }
+
                     // Update the value in the nodes set first before iterating.
-                    Self::propagate_to_parent(&mut nodes, &mut node);
+                    Self::propagate_to_parent(&mut nodes, &node);
                     node
                 }
                 ExpansionResult::Expanded(Expansion { parent, child }) => {
@@ -170,14 +762,19 @@ impl Solver {
         }
 
         let search_duration = Instant::now() - start_time;
-        Self::backtrack(
+        Some(Self::backtrack(
             nodes,
-            evaluations,
-            pruning_cuts,
-            max_visited_depth,
-            search_duration,
-            depth_limited,
-        )
+            table,
+            Statistics {
+                evaluations,
+                pruning_cuts,
+                transposition_hits,
+                max_visited_depth,
+                search_depth: max_depth,
+                search_duration,
+                depth_limited,
+            },
+        ))
     }
 
     /// Implements the minimax recursion as an expansion of the search tree.
@@ -186,118 +783,106 @@ impl Solver {
     /// * `node` - The search node we are expanding. We take ownership in order to avoid multiple borrows.
     /// * `next_child_id` - The next available child ID, typically the current length of the list
     ///   of all known and expanded nodes.
+    /// * `bounds` - The range of possible utility values, used to search the outcomes of chance nodes.
+    /// * `root_actions` - The only actions to search at the root, if the search is shared.
+    /// * `ordering` - Orders the actions of the node when it is expanded for the first time.
     ///
     /// ## Returns
     /// The same node that was passed in.
-    fn minimax_expand(mut node: Node, next_child_id: usize) -> ExpansionResult {
+    fn minimax_expand(
+        mut node: Node,
+        next_child_id: usize,
+        bounds: &UtilityBounds,
+        root_actions: Option<&[AppliedAction]>,
+        ordering: &MoveOrdering,
+    ) -> ExpansionResult {
         debug_assert!(next_child_id > node.id);
 
-        // Select the currently active party and the opponent.
-        let (current, opponent) = if node.is_maximizing {
-            (&node.state.initiator, &node.state.opponent)
-        } else {
-            (&node.state.opponent, &node.state.initiator)
-        };
+        // Members take actions in turns.
+        let source_party_id = node.state.acting_party().id;
+
+        // A chance node branches into the random outcomes of its action.
+        if node.chance.is_some() {
+            while let Some((probability, action)) = node.chance.as_mut().and_then(|c| c.next()) {
+                if let Some(state) = node.state.apply(source_party_id, &action) {
+                    let on_principal_variation = node.on_principal_variation
+                        && ordering.principal_action(node.depth) == Some(&action);
+                    let mut child_node = Node::new_outcome_from(
+                        next_child_id,
+                        &node,
+                        probability,
+                        action,
+                        state,
+                        bounds,
+                    );
+                    child_node.on_principal_variation = on_principal_variation;
+                    log_expand_chance_node(&node, &child_node, probability);
+                    return ExpansionResult::new_expansion(node, child_node);
+                }
+            }
+
+            return ExpansionResult::new_exhaustion(node);
+        }
 
-        // If the party retreated from the encounter, this is a terminal state.
-        if current.has_retreated() {
+        // A party that retreated from the encounter grants the other party
+        // one last action, after which this is a terminal state. The search
+        // always starts with an action, since its start may follow a retreat.
+        if node.action.is_some() && node.state.has_ended_after(node.action.as_ref()) {
             return ExpansionResult::new_exhaustion(node);
         }
 
-        // If we visit this node for the first time, create the iterator.
+        // If we visit this node for the first time, order its actions.
         // On all subsequent visits we continue from the last-known state.
         if node.action_iter.is_none() {
-            node.action_iter = Some(ActionIterator::new(current.clone(), opponent.clone()));
+            let iter = match node.state.actions() {
+                Some(iter) => iter,
+                None => return ExpansionResult::new_exhaustion(node),
+            };
+
+            let hash_action = node.hash_action.take();
+            node.action_iter = Some(ordering.order(
+                &node.state,
+                iter,
+                node.depth,
+                hash_action.as_ref(),
+                node.on_principal_variation,
+            ));
         }
 
-        // Members take actions in turns.
-        let source_party_id = current.id;
-
-        if let Some(action) = node.action_iter.as_mut().map_or(None, |i| i.next()) {
-            match action {
-                AppliedAction::Flee => {
-                    let mut current = current.clone();
-                    current.retreat();
-
-                    // Create a new branch on the board.
-                    let state = if node.is_maximizing {
-                        Conflict {
-                            initiator: current,
-                            opponent: opponent.clone(),
-                        }
-                    } else {
-                        Conflict {
-                            initiator: opponent.clone(),
-                            opponent: current,
-                        }
-                    };
+        while let Some(action) = node.action_iter.as_mut().and_then(|i| i.next()) {
+            if node.parent_id.is_none()
+                && root_actions.is_some_and(|actions| !actions.contains(&action))
+            {
+                continue;
+            }
 
-                    // If this is not the last member in the party we need to chain more
-                    // moves. This will create multiple maximize/minimize layers in the tree.
-                    let child_node = Node::new_branch_from(next_child_id, &node, action, state);
+            // Create a new branch on the board.
+            if let Some(state) = node.state.apply(source_party_id, &action) {
+                let on_principal_variation = node.on_principal_variation
+                    && ordering
+                        .principal_action(node.depth)
+                        .is_some_and(|result| action.has_outcome(result));
+
+                // Actions with random results are resolved by a chance node.
+                let outcomes = action.outcomes();
+                if outcomes.len() > 1 {
+                    let mut chance_node =
+                        Node::new_chance_from(next_child_id, &node, action, outcomes);
+                    chance_node.on_principal_variation = on_principal_variation;
+                    log_expand_node_with_chance(&node, &chance_node);
+                    return ExpansionResult::new_expansion(node, chance_node);
+                }
 
-                    if let Some(action) = &child_node.action {
-                        log_expand_node_with_action(&node, &child_node, &action);
-                    }
+                // If this is not the last member in the party we need to chain more
+                // moves. This will create multiple maximize/minimize layers in the tree.
+                let mut child_node = Node::new_branch_from(next_child_id, &node, action, state);
+                child_node.on_principal_variation = on_principal_variation;
 
-                    return ExpansionResult::new_expansion(node, child_node);
+                if let Some(action) = &child_node.action {
+                    log_expand_node_with_action(&node, &child_node, action);
                 }
-                AppliedAction::Targeted(action) => {
-                    let source_id = action.source.member_id;
-                    debug_assert_eq!(action.source.party_id, source_party_id);
-
-                    let target_party_id = action.target.party_id;
-                    debug_assert_eq!(action.target.party_id, opponent.id);
-
-                    let target_id = action.target.member_id;
-                    let target = &opponent.members[action.target.member_id];
-
-                    let action = &action.action;
-
-                    // TODO: Optimize state creation - only clone when action was applied.
-                    let mut target = target.clone();
-                    if target.handle_action(action) {
-                        // Branch off and replace the member with the updated state.
-                        let mut opponent = opponent.clone();
-                        opponent.replace_member(target);
-
-                        // Create a new branch on the board.
-                        let state = if node.is_maximizing {
-                            Conflict {
-                                initiator: current.clone(),
-                                opponent,
-                            }
-                        } else {
-                            Conflict {
-                                initiator: opponent,
-                                opponent: current.clone(),
-                            }
-                        };
-
-                        // The action to apply.
-                        let action = AppliedAction::Targeted(TargetedAction {
-                            action: action.clone(),
-                            source: Participant {
-                                party_id: source_party_id,
-                                member_id: source_id,
-                            },
-                            target: Participant {
-                                party_id: target_party_id,
-                                member_id: target_id,
-                            },
-                        });
-
-                        // If this is not the last member in the party we need to chain more
-                        // moves. This will create multiple maximize/minimize layers in the tree.
-                        let child_node = Node::new_branch_from(next_child_id, &node, action, state);
-
-                        if let Some(action) = &child_node.action {
-                            log_expand_node_with_action(&node, &child_node, &action);
-                        }
 
-                        return ExpansionResult::new_expansion(node, child_node);
-                    }
-                }
+                return ExpansionResult::new_expansion(node, child_node);
             }
         }
 
@@ -305,38 +890,31 @@ impl Solver {
     }
 
     /// Backtracks the events from the start to one of the the most likely outcomes.
-    fn backtrack(
-        nodes: Vec<Node>,
-        evaluations: usize,
-        pruning_cuts: usize,
-        max_visited_depth: usize,
-        search_duration: Duration,
-        depth_limited: bool,
-    ) -> Outcome {
+    fn backtrack(nodes: Vec<Node>, table: &TranspositionTable, stats: Statistics) -> Outcome {
         // The outcome is positive only if the value of the start
         // node is positive and under the assumption that the opposing
         // player attempts to play optimally.
-        let value = nodes[0].value.value;
-        let outcome = match value {
-            TerminalState::Win(score) => OutcomeType::Win(score),
-            TerminalState::Defeat(score) => OutcomeType::Lose(score),
-            TerminalState::Remain(score) => OutcomeType::Remain(score),
-            TerminalState::Retreat(score) => OutcomeType::Retreat(score),
-            TerminalState::Heuristic(score) => OutcomeType::Unknown(score),
-            TerminalState::OpenUnexplored(score) => OutcomeType::Unknown(score),
-        };
+        let outcome = OutcomeType::from(nodes[0].value.value);
 
         let mut stack = Vec::default();
 
         let mut node = &nodes[nodes[0].best_child.expect("A best child node is required")];
         'backtracking: loop {
-            stack.push(Event {
-                turn: node.turn,
-                is_initiator_turn: node.is_maximizing,
-                action: node.action.clone().expect(""),
-                state: node.state.clone(),
-                depth: node.depth,
-            });
+            // Chance nodes only select among the results of their action; the
+            // timeline continues with the most likely result.
+            if node.chance.is_none() {
+                // The node's side is the one to move next; the acting side is the parent's.
+                let parent = &nodes[node.parent_id.unwrap_or_default()];
+                stack.push(Event {
+                    turn: node.turn,
+                    party_id: parent.state.acting_party().id,
+                    is_initiator_turn: parent.is_maximizing,
+                    action: node.action.clone().expect(""),
+                    probability: node.probability,
+                    state: node.state.clone(),
+                    depth: node.depth,
+                });
+            }
 
             if let Some(best_child) = node.best_child {
                 node = &nodes[best_child];
@@ -346,20 +924,113 @@ impl Solver {
             break;
         }
 
+        // A line ending in a state resolved from the transposition table
+        // continues with the best actions stored there.
+        if node.from_transposition {
+            Self::backtrack_transpositions(node, table, &mut stack);
+        }
+
+        let results = PartyResult::from_state(stack.last().map_or(&nodes[0].state, |e| &e.state));
         Outcome {
             outcome,
+            results,
             timeline: stack,
-            evaluations,
-            cuts: pruning_cuts,
-            max_visited_depth,
-            search_duration,
-            depth_limited,
+            evaluations: stats.evaluations,
+            cuts: stats.pruning_cuts,
+            transposition_hits: stats.transposition_hits,
+            win_probability: nodes[0].win_probability,
+            max_visited_depth: stats.max_visited_depth,
+            search_depth: stats.search_depth,
+            search_duration: stats.search_duration,
+            depth_limited: stats.depth_limited,
+        }
+    }
+
+    /// Continues a timeline from a node by following the best actions
+    /// stored in the transposition table.
+    fn backtrack_transpositions(node: &Node, table: &TranspositionTable, stack: &mut Vec<Event>) {
+        let mut state = node.state.clone();
+        let mut is_maximizing = node.is_maximizing;
+
+        // Every state appears in the table at most once per side, so no line
+        // can be longer than the table itself. This also guards against cycles.
+        for step in 1..=table.len() {
+            let action = match table
+                .probe(&state, is_maximizing)
+                .and_then(|entry| entry.best_action.clone())
+            {
+                Some(action) => action,
+                None => break,
+            };
+
+            // Actions with random results continue with their most likely result.
+            let (probability, action) = action.most_likely_outcome();
+            let party_id = state.acting_party().id;
+            state = match state.apply(party_id, &action) {
+                Some(state) => state,
+                None => break,
+            };
+
+            let is_initiator_turn = is_maximizing;
+            is_maximizing = state.is_initiator_turn();
+            stack.push(Event {
+                turn: node.turn + step,
+                party_id,
+                is_initiator_turn,
+                action,
+                probability,
+                state: state.clone(),
+                depth: node.depth + step,
+            });
+        }
+    }
+
+    /// Remembers the action that caused a node to be cut off, so that it is tried
+    /// early in similar states.
+    fn record_cutoff(ordering: &mut MoveOrdering, nodes: &[Node], node: &Node, max_depth: usize) {
+        if let Some(action) = node.best_child.and_then(|id| nodes[id].action.as_ref()) {
+            ordering.record_cutoff(action, node.depth, max_depth - node.depth);
         }
     }
 
+    /// Stores the value of a node in the transposition table.
+    ///
+    /// ## Arguments
+    /// * `table` - The transposition table to update.
+    /// * `nodes` - All known nodes; used to determine the best action.
+    /// * `node` - The node whose value to store.
+    /// * `max_depth` - The maximum search depth of the current search.
+    /// * `bound` - How the node's value relates to its true value.
+    fn store_transposition(
+        table: &TranspositionTable,
+        nodes: &[Node],
+        node: &Node,
+        max_depth: usize,
+        bound: Bound,
+    ) {
+        let draft = if node.horizon_reached {
+            max_depth - node.depth
+        } else {
+            TranspositionEntry::UNLIMITED
+        };
+
+        let best_action = node.best_child.and_then(|id| nodes[id].action.clone());
+        table.store(
+            &node.state,
+            node.is_maximizing,
+            TranspositionEntry {
+                draft,
+                value: node.value.value,
+                bound,
+                best_action,
+                win_probability: node.win_probability,
+            },
+        );
+    }
+
     /// Propagates known terminal utility values upwards in the
     /// search tree.
-    fn propagate_to_parent(nodes: &mut Vec<Node>, child_node: &Node) -> Cutoff {
+    fn propagate_to_parent(nodes: &mut [Node], child_node: &Node) -> Cutoff {
         let parent_id = child_node.parent_id;
 
         if let Some(id) = parent_id {
@@ -369,10 +1040,21 @@ impl Solver {
             let child_value = child_node.value.clone();
 
             let parent_node = &mut nodes[id];
-            if parent_node.is_maximizing {
+            parent_node.horizon_reached |= child_node.horizon_reached;
+            if let Some(chance) = parent_node.chance.as_mut() {
+                // The outcomes of a chance node contribute by their probability.
+                chance.expected += child_node.probability * child_value.value();
+                chance.remaining -= child_node.probability;
+                if parent_node.best_child.is_none() {
+                    parent_node.best_child = Some(child_node.id);
+                }
+
+                parent_node.win_probability += child_node.probability * child_node.win_probability;
+            } else if parent_node.is_maximizing {
                 if child_value.value > *parent_node.value {
                     *parent_node.value = child_value.value;
                     parent_node.best_child = Some(child_node.id);
+                    parent_node.win_probability = child_node.win_probability;
                 }
 
                 if parent_node.value.is_beta_cutoff() {
@@ -386,6 +1068,7 @@ impl Solver {
                 if child_value.value < *parent_node.value {
                     *parent_node.value = child_value.value;
                     parent_node.best_child = Some(child_node.id);
+                    parent_node.win_probability = child_node.win_probability;
                 }
 
                 if parent_node.value.is_alpha_cutoff() {
@@ -402,59 +1085,288 @@ impl Solver {
 }
 
 /// An outcome of a conflict.
+#[derive(Serialize)]
 pub struct Outcome {
     /// Whether the initiating party wins the conflict.
     pub outcome: OutcomeType,
+    /// The result for each party at the end of the timeline.
+    pub results: Vec<PartyResult>,
     /// An optimal path of actions leading to the outcome.
     pub timeline: Vec<Event>,
     /// The number of node evaluations performed.
     pub evaluations: usize,
     /// The number of pruning steps performed.
     pub cuts: usize,
-    /// The search duration.
+    /// The number of states resolved from the transposition table.
+    pub transposition_hits: usize,
+    /// The probability of the initiating party winning within the search horizon,
+    /// assuming both parties play optimally. States at the horizon only count if they
+    /// are already won, not by their heuristic value. Monte Carlo Tree Search estimates it
+    /// from the playouts of the most promising action.
+    pub win_probability: f32,
+    /// The search duration. Serialized in seconds.
+    #[serde(serialize_with = "serialize_seconds")]
     pub search_duration: Duration,
     /// The depth of the deepest node evaluated.
     pub max_visited_depth: usize,
+    /// The depth limit of the search that produced the outcome. For time-limited
+    /// searches, this is the depth of the deepest complete iteration.
+    pub search_depth: usize,
     /// `true` if the search was depth limited and has more nodes to explore.
     pub depth_limited: bool,
 }
 
 impl Outcome {
+    /// Returns the number of events in the timeline.
     pub fn len(&self) -> usize {
         self.timeline.len()
     }
+
+    /// Returns `true` if the timeline has no events.
+    pub fn is_empty(&self) -> bool {
+        self.timeline.is_empty()
+    }
+}
+
+/// An action available in the current state, as evaluated by [`Solver::advise`].
+#[derive(Serialize)]
+pub struct Advice {
+    /// The action, before any of its random results are rolled.
+    pub action: AppliedAction,
+    /// The outcome of the conflict after taking the action, in the eyes of the initiating party.
+    pub outcome: OutcomeType,
+    /// The probability of the initiating party winning after taking the action.
+    pub win_probability: f32,
+    /// The expected utility of the action in the eyes of the acting party, by which
+    /// the advice is ranked.
+    pub utility: f32,
+    /// The principal variation: the course of the conflict both sides aim for, starting
+    /// with the most likely result of the action.
+    pub principal_variation: Vec<Event>,
 }
 
 /// The type of outcome.
-#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Serialize)]
+#[serde(tag = "type", content = "score", rename_all = "snake_case")]
 pub enum OutcomeType {
     /// The initiating party wins.
     Win(f32),
     /// The initiating party loses.
     Lose(f32),
-    /// The initiating party remained after the opponent retreated.
+    /// The initiating party remained after its last opponents retreated.
     Remain(f32),
     /// The initiating party retreated.
     Retreat(f32),
     /// Unknown outcome.
     Unknown(f32),
+    /// The outcome depends on chance; the score is the expected value.
+    Expected(f32),
+}
+
+impl OutcomeType {
+    /// Gets the score of the outcome.
+    pub const fn score(&self) -> f32 {
+        match self {
+            OutcomeType::Win(score) => *score,
+            OutcomeType::Lose(score) => *score,
+            OutcomeType::Remain(score) => *score,
+            OutcomeType::Retreat(score) => *score,
+            OutcomeType::Unknown(score) => *score,
+            OutcomeType::Expected(score) => *score,
+        }
+    }
+}
+
+impl From<TerminalState> for OutcomeType {
+    fn from(value: TerminalState) -> Self {
+        match value {
+            TerminalState::Win(score) => OutcomeType::Win(score),
+            TerminalState::Defeat(score) => OutcomeType::Lose(score),
+            TerminalState::Remain(score) => OutcomeType::Remain(score),
+            TerminalState::Retreat(score) => OutcomeType::Retreat(score),
+            TerminalState::Heuristic(score) => OutcomeType::Unknown(score),
+            TerminalState::OpenUnexplored(score) => OutcomeType::Unknown(score),
+            TerminalState::Expected(score) => OutcomeType::Expected(score),
+        }
+    }
+}
+
+impl From<OutcomeType> for TerminalState {
+    fn from(value: OutcomeType) -> Self {
+        match value {
+            OutcomeType::Win(score) => TerminalState::Win(score),
+            OutcomeType::Lose(score) => TerminalState::Defeat(score),
+            OutcomeType::Remain(score) => TerminalState::Remain(score),
+            OutcomeType::Retreat(score) => TerminalState::Retreat(score),
+            OutcomeType::Unknown(score) => TerminalState::Heuristic(score),
+            OutcomeType::Expected(score) => TerminalState::Expected(score),
+        }
+    }
+}
+
+/// The result of a conflict for a single party.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize)]
+pub struct PartyResult {
+    /// The ID of the party.
+    pub party_id: usize,
+    /// How the party fared.
+    pub outcome: PartyOutcome,
+}
+
+/// How a party fared in a conflict.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PartyOutcome {
+    /// The party remained after all parties outside its alliance were defeated or retreated.
+    Victorious,
+    /// All members of the party are dead.
+    Defeated,
+    /// The party retreated from the encounter.
+    Retreated,
+    /// The party is still fighting, e.g. because the search was depth limited.
+    Fighting,
+}
+
+impl PartyResult {
+    /// Determines the result of every party in a state of the conflict.
+    pub fn from_state(state: &Conflict) -> Vec<Self> {
+        state
+            .parties
+            .iter()
+            .map(|party| {
+                let outcome = if party.is_defeated() {
+                    PartyOutcome::Defeated
+                } else if party.has_retreated() {
+                    PartyOutcome::Retreated
+                } else if state
+                    .parties
+                    .iter()
+                    .all(|other| other.is_allied_with(party) || !other.is_engaged())
+                {
+                    PartyOutcome::Victorious
+                } else {
+                    PartyOutcome::Fighting
+                };
+
+                Self {
+                    party_id: party.id,
+                    outcome,
+                }
+            })
+            .collect()
+    }
 }
 
 /// An event in the timeline.
+#[derive(Serialize)]
 pub struct Event {
     /// The turn in which an event took place.
     pub turn: usize,
-    /// Whether this turn is performed by the initiating party.
+    /// The ID of the party performing this turn.
+    pub party_id: usize,
+    /// Whether this turn is performed by the initiating party or one of its allies.
     pub is_initiator_turn: bool,
     /// The action that was applied.
     pub action: AppliedAction,
+    /// The probability of the action having this result; one unless the result was rolled.
+    pub probability: f32,
     /// The depth at which this node was discovered.
     pub depth: usize,
     /// The state of the conflict after the action took place.
     pub state: Conflict,
 }
 
+/// Serializes a duration as fractional seconds.
+pub fn serialize_seconds<S: Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
+}
+
+/// Statistics collected during a search.
+struct Statistics {
+    /// The number of node evaluations performed.
+    evaluations: usize,
+    /// The number of pruning steps performed.
+    pruning_cuts: usize,
+    /// The number of states resolved from the transposition table.
+    transposition_hits: usize,
+    /// The depth of the deepest node evaluated.
+    max_visited_depth: usize,
+    /// The depth limit of the search.
+    search_depth: usize,
+    /// The search duration.
+    search_duration: Duration,
+    /// `true` if the search was depth limited and has more nodes to explore.
+    depth_limited: bool,
+}
+
+impl Statistics {
+    /// Adds the work of another search.
+    fn add(&mut self, outcome: &Outcome) {
+        self.evaluations += outcome.evaluations;
+        self.pruning_cuts += outcome.cuts;
+        self.transposition_hits += outcome.transposition_hits;
+        self.max_visited_depth = self.max_visited_depth.max(outcome.max_visited_depth);
+        self.depth_limited |= outcome.depth_limited;
+    }
+}
+
+impl From<&Outcome> for Statistics {
+    fn from(outcome: &Outcome) -> Self {
+        Self {
+            evaluations: outcome.evaluations,
+            pruning_cuts: outcome.cuts,
+            transposition_hits: outcome.transposition_hits,
+            max_visited_depth: outcome.max_visited_depth,
+            search_depth: outcome.search_depth,
+            search_duration: outcome.search_duration,
+            depth_limited: outcome.depth_limited,
+        }
+    }
+}
+
+/// The share of the actions at the root that a search explores, so that several
+/// threads can share the work of a single search.
+struct RootSplit<'a> {
+    /// The root actions to search.
+    actions: &'a [AppliedAction],
+    /// The lower bound of the search window at the root.
+    alpha: f32,
+    /// The upper bound of the search window at the root.
+    beta: f32,
+}
+
+impl<'a> RootSplit<'a> {
+    /// Creates a split searching a single root action.
+    ///
+    /// ## Arguments
+    /// * `action` - The root action to search.
+    /// * `is_maximizing` - Whether the initiating party acts at the root.
+    /// * `bound` - The best value of the root actions searched so far, if any.
+    fn new(action: &'a AppliedAction, is_maximizing: bool, bound: Option<f32>) -> Self {
+        let (alpha, beta) = match bound {
+            Some(bound) if is_maximizing => (bound, f32::INFINITY),
+            Some(bound) => (f32::NEG_INFINITY, bound),
+            None => (f32::NEG_INFINITY, f32::INFINITY),
+        };
+
+        Self {
+            actions: std::slice::from_ref(action),
+            alpha,
+            beta,
+        }
+    }
+
+    /// Determines whether a value lies within the search window, i.e. is exact.
+    fn contains(&self, value: f32) -> bool {
+        self.alpha < value && value < self.beta
+    }
+}
+
 /// A tree expansion outcome.
+#[allow(clippy::large_enum_variant)]
 enum ExpansionResult {
     /// A new node was added.
     Expanded(Expansion),
@@ -510,7 +1422,7 @@ struct Node {
     /// The depth of the node. If it reaches zero, search is terminated.
     pub depth: usize,
     /// The action iterator; if it yields none, the node is explored completely.
-    pub action_iter: Option<ActionIterator>,
+    pub action_iter: Option<OrderedActions>,
     /// Whether this is a maximizing or minimizing node in minimax.
     /// If maximizing, the represents a move of the initiating party of the conflict.
     pub is_maximizing: bool,
@@ -525,27 +1437,77 @@ struct Node {
     pub action: Option<AppliedAction>,
     /// The state after applying the action.
     pub state: Conflict,
+    /// The lower bound of the search window when the node was created.
+    pub window_alpha: f32,
+    /// The upper bound of the search window when the node was created.
+    pub window_beta: f32,
+    /// Whether the search below this node was limited by the search depth,
+    /// i.e. whether the node's value depends on the search depth.
+    pub horizon_reached: bool,
+    /// Whether the node's value was taken from the transposition table.
+    pub from_transposition: bool,
+    /// The best action known from the transposition table, to be tried first.
+    pub hash_action: Option<AppliedAction>,
+    /// Whether the node lies on the principal variation of the previous iteration.
+    pub on_principal_variation: bool,
+    /// The random outcomes of the action if this is a chance node.
+    /// A chance node holds the state before its action is applied.
+    pub chance: Option<Chance>,
+    /// The probability of this node's action having its result.
+    pub probability: f32,
+    /// The probability of the initiating party winning from this node.
+    pub win_probability: f32,
+}
+
+/// The outcomes of a chance node, i.e. a node whose children are the
+/// random results of a single action.
+#[derive(Debug, Clone)]
+struct Chance {
+    /// The possible results of the action and their probabilities,
+    /// starting with the most likely one.
+    outcomes: Vec<(f32, AppliedAction)>,
+    /// The index of the next outcome to explore.
+    next: usize,
+    /// The sum of the probability-weighted values of all explored outcomes.
+    expected: f32,
+    /// The total probability of all unexplored outcomes.
+    remaining: f32,
 }
 
 impl Node {
     /// Creates a new root node.
     pub fn new_root(conflict: Conflict, max_depth: usize) -> Self {
+        let is_maximizing = conflict.is_initiator_turn();
         Self {
             id: 0,
             parent_id: None,
             depth: max_depth,
             turn: 0,
-            is_maximizing: true,
-            value: Value::new(TerminalState::Heuristic(f32::NEG_INFINITY)),
+            is_maximizing,
+            value: if is_maximizing {
+                Value::new(TerminalState::Heuristic(f32::NEG_INFINITY))
+            } else {
+                Value::new(TerminalState::Heuristic(f32::INFINITY))
+            },
             best_child: None,
             action: None,
             state: conflict,
             action_iter: None,
+            window_alpha: f32::NEG_INFINITY,
+            window_beta: f32::INFINITY,
+            horizon_reached: false,
+            from_transposition: false,
+            hash_action: None,
+            on_principal_variation: true,
+            chance: None,
+            probability: 1.0,
+            win_probability: 0.0,
         }
     }
 
-    /// Ends this party's turn and by changing from maximizing to minimizing
-    /// and vice versa.
+    /// Ends the current turn by handing over to the party acting next, i.e. the next
+    /// party when turns alternate, or the party of the next member in initiative order.
+    /// The node maximizes if that party is allied with the initiator and minimizes otherwise.
     ///
     /// ## Arguments
     /// * `id` - The new ID for the node to be created.
@@ -558,7 +1520,7 @@ impl Node {
         action: AppliedAction,
         state: Conflict,
     ) -> Self {
-        let is_maximizing = !parent.is_maximizing;
+        let is_maximizing = state.is_initiator_turn();
         Self {
             id,
             parent_id: Some(parent.id),
@@ -578,13 +1540,168 @@ impl Node {
             action: Some(action),
             action_iter: None,
             state,
+            window_alpha: parent.value.alpha,
+            window_beta: parent.value.beta,
+            horizon_reached: false,
+            from_transposition: false,
+            hash_action: None,
+            on_principal_variation: false,
+            chance: None,
+            probability: 1.0,
+            win_probability: 0.0,
+        }
+    }
+
+    /// Creates a chance node resolving the random results of an action.
+    /// The node keeps the parent's state and side to move.
+    ///
+    /// ## Arguments
+    /// * `id` - The new ID for the node to be created.
+    /// * `parent` - The parent node.
+    /// * `action` - The action whose results are resolved.
+    /// * `outcomes` - The results of the action and their probabilities.
+    pub fn new_chance_from(
+        id: usize,
+        parent: &Node,
+        action: AppliedAction,
+        outcomes: Vec<(f32, AppliedAction)>,
+    ) -> Self {
+        Self {
+            id,
+            parent_id: Some(parent.id),
+            is_maximizing: parent.is_maximizing,
+            value: parent.value.with_value(TerminalState::Expected(0.0)),
+            best_child: None,
+            depth: parent.depth,
+            turn: parent.turn,
+            action: Some(action),
+            action_iter: None,
+            state: parent.state.clone(),
+            window_alpha: parent.value.alpha,
+            window_beta: parent.value.beta,
+            horizon_reached: false,
+            from_transposition: false,
+            hash_action: None,
+            on_principal_variation: false,
+            chance: Some(Chance::new(outcomes)),
+            probability: 1.0,
+            win_probability: 0.0,
+        }
+    }
+
+    /// Creates a node for one of the results of a chance node's action. The node
+    /// is searched within the window that would cut off the chance node.
+    ///
+    /// ## Arguments
+    /// * `id` - The new ID for the node to be created.
+    /// * `parent` - The parent chance node.
+    /// * `probability` - The probability of the result.
+    /// * `action` - The action with its result.
+    /// * `state` - The new state observed by the child node after applying the action.
+    /// * `bounds` - The range of possible utility values.
+    pub fn new_outcome_from(
+        id: usize,
+        parent: &Node,
+        probability: f32,
+        action: AppliedAction,
+        state: Conflict,
+        bounds: &UtilityBounds,
+    ) -> Self {
+        let mut node = Self::new_branch_from(id, parent, action, state);
+        if let Some(chance) = &parent.chance {
+            let (alpha, beta) =
+                chance.child_window(probability, parent.value.alpha, parent.value.beta, bounds);
+            node.value.alpha = alpha;
+            node.value.beta = beta;
+            node.window_alpha = alpha;
+            node.window_beta = beta;
+        }
+
+        node.probability = probability;
+        node
+    }
+
+    /// Determines how the value of a fully explored node relates to its
+    /// true value, given the search window the node was explored with.
+    pub fn window_bound(&self) -> Bound {
+        if self.value.value() <= self.window_alpha {
+            Bound::Upper
+        } else if self.value.value() >= self.window_beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
         }
     }
 }
 
+impl Chance {
+    /// Creates the chance state for the results of an action. The most likely
+    /// result is explored first and continues the timeline.
+    fn new(mut outcomes: Vec<(f32, AppliedAction)>) -> Self {
+        let most_likely = outcomes.remove(AppliedAction::most_likely(&outcomes));
+        outcomes.insert(0, most_likely);
+        Self {
+            outcomes,
+            next: 0,
+            expected: 0.0,
+            remaining: 1.0,
+        }
+    }
+
+    /// Gets the next result to explore and its probability.
+    fn next(&mut self) -> Option<(f32, AppliedAction)> {
+        let outcome = self.outcomes.get(self.next).cloned();
+        self.next += 1;
+        outcome
+    }
+
+    /// Determines whether the explored results already place the expected value
+    /// outside the search window, assuming the unexplored results are as good or
+    /// as bad as possible.
+    ///
+    /// ## Returns
+    /// The bound of the expected value that lies outside the window, or [`None`]
+    /// if the remaining results need to be explored.
+    fn cutoff(&self, alpha: f32, beta: f32, bounds: &UtilityBounds) -> Option<TerminalState> {
+        let remaining = self.remaining.max(0.0);
+        let upper = self.expected + remaining * bounds.upper;
+        let lower = self.expected + remaining * bounds.lower;
+        if upper <= alpha {
+            Some(TerminalState::Expected(upper))
+        } else if lower >= beta {
+            Some(TerminalState::Expected(lower))
+        } else {
+            None
+        }
+    }
+
+    /// Determines the search window of the next result: any value outside of it
+    /// moves the expected value outside the chance node's window.
+    ///
+    /// ## Arguments
+    /// * `probability` - The probability of the next result.
+    /// * `alpha` - The lower bound of the chance node's search window.
+    /// * `beta` - The upper bound of the chance node's search window.
+    /// * `bounds` - The range of possible utility values.
+    fn child_window(
+        &self,
+        probability: f32,
+        alpha: f32,
+        beta: f32,
+        bounds: &UtilityBounds,
+    ) -> (f32, f32) {
+        let others = (self.remaining - probability).max(0.0);
+        let alpha = (alpha - self.expected - others * bounds.upper) / probability;
+        let beta = (beta - self.expected - others * bounds.lower) / probability;
+        (alpha, beta)
+    }
+}
+
 impl Display for Node {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if self.is_maximizing {
+        if self.chance.is_some() {
+            write!(f, "*{}", self.id)
+        } else if self.is_maximizing {
             write!(f, "↑{}", self.id)
         } else {
             write!(f, "↓{}", self.id)
@@ -631,14 +1748,6 @@ fn log_beta_cutoff(node: &Node) {
     );
 }
 
-#[inline]
-fn log_minimizer_detected_defeat(node: &Node) {
-    trace!(
-        "Minimizer found defeat with value={value} - stopping expansion",
-        value = node.value.value
-    );
-}
-
 #[inline]
 fn log_node_fully_explored(node: &Node, nodes: &[Node]) {
     trace!(
@@ -671,17 +1780,48 @@ fn log_node_terminal_state(node: &Node, value: &TerminalState, nodes: &[Node]) {
             "Node {node} (child of {parent_node}) exhausted, got value {value} (heuristic)",
             parent_node = nodes[node.parent_id.unwrap_or(0)]
         ),
+        TerminalState::Expected(_) => {
+            unreachable!("A chance node is never terminal")
+        }
         TerminalState::OpenUnexplored(_) => {
             unreachable!("An open/unexplored node must have a defined terminal value")
         }
     }
 }
 
+#[inline]
+fn log_transposition_hit(node: &Node, value: &TerminalState) {
+    trace!("Node {node} resolved from transposition table with value {value}");
+}
+
 #[inline]
 fn log_expand_node_with_action(node: &Node, child_node: &Node, action: &AppliedAction) {
     trace!("Expand node {node} into {child_node} with action: {action}");
 }
 
+#[inline]
+fn log_expand_node_with_chance(node: &Node, chance_node: &Node) {
+    if let Some(action) = &chance_node.action {
+        trace!("Expand node {node} into chance node {chance_node} with action: {action}");
+    }
+}
+
+#[inline]
+fn log_expand_chance_node(node: &Node, child_node: &Node, probability: f32) {
+    if let Some(action) = &child_node.action {
+        trace!("Expand chance node {node} into {child_node} with p={probability}: {action}");
+    }
+}
+
+#[inline]
+fn log_chance_cutoff(node: &Node, value: &TerminalState) {
+    trace!(
+        "Chance cutoff at bound {value} outside α={alpha} β={beta} - stopping expansion",
+        alpha = node.value.alpha,
+        beta = node.value.beta
+    );
+}
+
 #[inline]
 fn log_increase_search_depth_to(depth: usize, max_depth: usize) {
     trace!("Increase search depth to {depth}/{max_depth}");
@@ -692,6 +1832,11 @@ fn log_stop_iddfs(max_depth: usize) {
     trace!("Iterative deepening DFS stopped at depth {max_depth}");
 }
 
+#[inline]
+fn log_search_timed_out(depth: usize) {
+    trace!("Search timed out at depth {depth}; using the previous iteration");
+}
+
 #[inline]
 fn log_finish_iddfs(depth: usize) {
     trace!("Iterative deepening DFS finished at depth {depth}");
@@ -700,9 +1845,15 @@ fn log_finish_iddfs(depth: usize) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::action::{Action, HealAction, TargetedAction};
+    use crate::catalog::WeaponCatalog;
+    use crate::conflict::TurnOrder;
     use crate::party::Party;
-    use crate::party_member::PartyMember;
-    use crate::weapon::{Fists, Stick, Weapon};
+    use crate::party_member::{PartyMember, ResourcePool, Row};
+    use crate::scenario::Scenario;
+    use crate::status_effect::{StatusEffect, StatusEffectKind};
+    use crate::utility_value::Personality;
+    use crate::weapon::Weapon;
 
     #[test]
     fn simple_fight_works() {
@@ -710,11 +1861,16 @@ mod tests {
         let villains = build_simple_villain_party();
 
         let conflict = Conflict {
-            initiator: heroes,
-            opponent: villains,
+            parties: vec![heroes, villains],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
         };
 
-        let solution = Solver::engage(&conflict, SolverStrategy::DepthLimited(200));
+        let solution = Solver::engage(
+            &conflict,
+            SolverStrategy::DepthLimited(200),
+            &Personality::default(),
+        );
         assert_eq!(solution.outcome, OutcomeType::Win(5.0));
     }
 
@@ -724,13 +1880,18 @@ mod tests {
         let villains = build_complex_villain_party(false, 10.0);
 
         let conflict = Conflict {
-            initiator: heroes,
-            opponent: villains,
+            parties: vec![heroes, villains],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
         };
 
         // In this version, the enemy is not allowed to flee, so the
         // game takes five turns (three strikes for the heros).
-        let solution = Solver::engage(&conflict, SolverStrategy::DepthLimited(100));
+        let solution = Solver::engage(
+            &conflict,
+            SolverStrategy::DepthLimited(100),
+            &Personality::default(),
+        );
         assert_eq!(solution.outcome, OutcomeType::Win(10.0));
         assert_eq!(solution.len(), 5);
     }
@@ -741,8 +1902,9 @@ mod tests {
         let villains = build_complex_villain_party(true, 10.0); // 👈 equal health to the hero damage
 
         let conflict = Conflict {
-            initiator: heroes,
-            opponent: villains,
+            parties: vec![heroes, villains],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
         };
 
         // The enemy slightly prefers dealing damage over retaining health,
@@ -750,28 +1912,194 @@ mod tests {
         // is to flee after the first initiator move. Since the remaining
         // party always has one extra move, the hero gets either two strikes
         // or three, but three strikes are enough to defeat the enemy.
-        let solution = Solver::engage(&conflict, SolverStrategy::DepthLimited(100));
+        let solution = Solver::engage(
+            &conflict,
+            SolverStrategy::DepthLimited(100),
+            &Personality::default(),
+        );
         assert_eq!(solution.outcome, OutcomeType::Remain(2.0));
         assert_eq!(solution.len(), 3);
     }
 
+    #[test]
+    fn personalities_weigh_outcomes() {
+        let heroes = build_default_hero_party(true, 20.0);
+        let villains = build_complex_villain_party(true, 10.0);
+        let conflict = Conflict {
+            parties: vec![heroes, villains],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
+        };
+
+        // Damage dealt counts as much as health kept, doubling the value of the retreat.
+        let solution = Solver::engage(
+            &conflict,
+            SolverStrategy::DepthLimited(100),
+            &Personality::aggressive(),
+        );
+        assert_eq!(solution.outcome, OutcomeType::Remain(4.0));
+
+        // A cautious hero is glad to see the enemy leave, so the enemy rather fights on.
+        let solution = Solver::engage(
+            &conflict,
+            SolverStrategy::DepthLimited(100),
+            &Personality::cautious(),
+        );
+        assert!(matches!(solution.outcome, OutcomeType::Win(_)));
+    }
+
+    #[test]
+    fn advice_ranks_available_actions() {
+        let heroes = build_default_hero_party(false, 20.0);
+        let villains = build_complex_villain_party(true, 10.0);
+        let conflict = Conflict {
+            parties: vec![heroes, villains],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
+        };
+
+        // The hero strikes down the dangerous villain before it gets to act;
+        // attacking the other villain first ends in a defeat.
+        let strategy = SolverStrategy::DepthLimited(100);
+        let advice = Solver::advise(&conflict, strategy, &Personality::default());
+        match &advice[0].action {
+            AppliedAction::Targeted(action) => assert_eq!(action.target.member_id, 1),
+            action => panic!("unexpected action: {action}"),
+        }
+        assert!(matches!(
+            advice.last().map(|a| a.outcome),
+            Some(OutcomeType::Lose(_))
+        ));
+
+        let solution = Solver::engage(&conflict, strategy, &Personality::default());
+        assert_eq!(advice[0].outcome, solution.outcome);
+        assert_eq!(advice[0].principal_variation.len(), solution.len());
+        assert_eq!(advice[0].principal_variation[0].action, advice[0].action);
+
+        // Applying the advice turn by turn, the villains flee, granting the hero one last action.
+        let mut state = conflict.clone();
+        for _ in 0..2 {
+            let party_id = state.acting_party().id;
+            let advice = Solver::advise(&state, strategy, &Personality::default());
+            state = state.apply(party_id, &advice[0].action).unwrap();
+        }
+        assert!(state.parties[1].has_retreated());
+
+        let advice = Solver::advise(&state, strategy, &Personality::default());
+        assert!(!advice.is_empty());
+        assert_eq!(advice[0].outcome, solution.outcome);
+    }
+
+    #[test]
+    fn advice_searches_one_level_less_after_deterministic_actions() {
+        let weapon = Weapon {
+            name: "their fists".to_string(),
+            damage: 1.0,
+            ..Default::default()
+        };
+        let conflict = Conflict {
+            parties: vec![
+                Party::new(0, vec![PartyMember::new(0, 100.0, weapon.clone())]),
+                Party::new(1, vec![PartyMember::new(0, 100.0, weapon)]),
+            ],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
+        };
+
+        // The action itself plus a search of the remaining depth after it.
+        let max_depth = 6;
+        let strategy = SolverStrategy::DepthLimited(max_depth);
+        let advice = Solver::advise(&conflict, strategy, &Personality::default());
+        assert!(!advice.is_empty());
+        for advice in &advice {
+            assert_eq!(advice.action.outcomes().len(), 1);
+            assert_eq!(advice.principal_variation.len(), max_depth);
+            assert_eq!(
+                advice.principal_variation.last().map(|event| event.depth),
+                Some(max_depth)
+            );
+        }
+    }
+
+    #[test]
+    fn advice_follows_the_preferences_of_the_acting_party() {
+        let heroes = build_default_hero_party(false, 30.0);
+        let villains: Vec<_> = (1..=2)
+            .map(|id| {
+                let mut party = build_default_hero_party(true, 20.0);
+                party.id = id;
+                party.alliance = id;
+                party
+            })
+            .collect();
+        let mut conflict = Conflict {
+            parties: [vec![heroes], villains].concat(),
+            turn_order: TurnOrder::Alternating { party: 1 },
+            formations: false,
+        };
+
+        // Playing against the hero, the first villain joins in on the attack.
+        let strategy = SolverStrategy::DepthLimited(8);
+        let advice = Solver::advise(&conflict, strategy, &Personality::default());
+        match &advice[0].action {
+            AppliedAction::Targeted(action) => assert_eq!(action.target.party_id, 0),
+            action => panic!("unexpected action: {action}"),
+        }
+
+        // Caring for its own hide, it rather leaves the others to it.
+        for party in conflict.parties[1..].iter_mut() {
+            party.personality = Some(Personality::cautious());
+        }
+        let advice = Solver::advise(&conflict, strategy, &Personality::default());
+        assert_eq!(advice[0].action, AppliedAction::Flee);
+        assert!(advice.windows(2).all(|a| a[0].utility >= a[1].utility));
+    }
+
     #[test]
     fn complex_fight_initiator_flees() {
-        let heroes = build_default_hero_party(true, 20.0); // 👈 an opponent exists that does equal damage
+        let heroes = build_default_hero_party(true, 25.0); // 👈 survives a single blow of any opponent
         let villains = build_complex_villain_party(true, 15.0); // 👈 more health than hero does damage
 
         let conflict = Conflict {
-            initiator: heroes,
-            opponent: villains,
+            parties: vec![heroes, villains],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
         };
 
-        let solution = Solver::engage(&conflict, SolverStrategy::DepthLimited(100));
+        let solution = Solver::engage(
+            &conflict,
+            SolverStrategy::DepthLimited(100),
+            &Personality::default(),
+        );
 
-        // Since the hero will be one-hit by the second enemy, the only
+        // Since the hero cannot survive two blows of the second enemy, the only
         // meaningful action is to flee in turn one.
         // Due to the mechanics, the enemy will get an extra turn, resulting
         // in a two-turn outcome.
-        assert_eq!(solution.outcome, OutcomeType::Retreat(-0.5));
+        assert_eq!(solution.outcome, OutcomeType::Retreat(-2.0));
+        assert_eq!(solution.len(), 2);
+    }
+
+    #[test]
+    fn complex_fight_initiator_cannot_flee_a_fatal_blow() {
+        let heroes = build_default_hero_party(true, 20.0); // 👈 an opponent exists that does equal damage
+        let villains = build_complex_villain_party(true, 15.0); // 👈 more health than hero does damage
+
+        let conflict = Conflict {
+            parties: vec![heroes, villains],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
+        };
+
+        let solution = Solver::engage(
+            &conflict,
+            SolverStrategy::DepthLimited(100),
+            &Personality::default(),
+        );
+
+        // The hero will be one-hit by the second enemy, and fleeing grants
+        // that enemy the extra turn it needs. Whatever the hero does, it is defeated.
+        assert_eq!(solution.outcome, OutcomeType::Lose(-20.0));
         assert_eq!(solution.len(), 2);
     }
 
@@ -781,74 +2109,633 @@ mod tests {
         let villains = build_complex_villain_party(true, 15.0); // 👈 more health than hero does damage
 
         let conflict = Conflict {
-            initiator: heroes,
-            opponent: villains,
+            parties: vec![heroes, villains],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
         };
 
-        let solution = Solver::engage(&conflict, SolverStrategy::DepthLimited(100));
+        let solution = Solver::engage(
+            &conflict,
+            SolverStrategy::DepthLimited(100),
+            &Personality::default(),
+        );
 
-        // In this setup the hero is not allowed to flee, leading to a defeat.
+        // In this setup the hero is not allowed to flee, leading to a defeat
+        // by the second enemy's first blow.
         assert_eq!(solution.outcome, OutcomeType::Lose(-20.0));
-        assert_eq!(solution.len(), 8);
+        assert_eq!(solution.len(), 2);
+    }
+
+    #[test]
+    fn transpositions_are_reused() {
+        let mut heroes = build_default_hero_party(false, 30.0);
+        heroes.members.push(PartyMember {
+            id: 1,
+            health: 30.0,
+            max_health: 30.0,
+            damage_taken: 0.0,
+            damage_mitigated: 0.0,
+            weapon: Weapon {
+                name: "a stick".to_string(),
+                damage: 5.0,
+                ..Default::default()
+            },
+            can_act: true,
+            speed: 0,
+            row: Row::Front,
+            stance: None,
+            escaped: false,
+            armor: 0.0,
+            resistances: Vec::default(),
+            damage_bonus: 0.0,
+            abilities: Vec::default(),
+            mana: ResourcePool::default(),
+            stamina: ResourcePool::default(),
+            spells: Vec::default(),
+            effects: Vec::default(),
+        });
+        let villains = build_complex_villain_party(false, 10.0);
+
+        let conflict = Conflict {
+            parties: vec![heroes, villains],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
+        };
+
+        // Both heroes hitting the enemies in either order leads to the same state.
+        let solution = Solver::engage(
+            &conflict,
+            SolverStrategy::IterativeDeepening(8),
+            &Personality::default(),
+        );
+        assert!(solution.transposition_hits > 0);
+        assert!(matches!(solution.outcome, OutcomeType::Win(_)));
+    }
+
+    #[test]
+    fn parallel_search_matches_sequential_search() {
+        let mut heroes = build_default_hero_party(false, 30.0);
+        let mut sidekick = heroes.members[0].clone();
+        sidekick.id = 1;
+        sidekick.weapon.damage = 5.0;
+        heroes.members.push(sidekick);
+        let villains = build_complex_villain_party(true, 20.0);
+
+        let conflict = Conflict {
+            parties: vec![heroes, villains],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
+        };
+
+        for strategy in [
+            SolverStrategy::DepthLimited(6),
+            SolverStrategy::IterativeDeepening(12),
+        ] {
+            let sequential = Solver::engage(&conflict, strategy, &Personality::default());
+            for threads in [2, 4] {
+                let parallel =
+                    Solver::engage_parallel(&conflict, strategy, &Personality::default(), threads);
+                assert_eq!(parallel.outcome, sequential.outcome);
+                assert_eq!(parallel.search_depth, sequential.search_depth);
+            }
+        }
+    }
+
+    #[test]
+    fn parallel_search_matches_sequential_search_with_chance_and_more_parties() {
+        let catalog = WeaponCatalog::builtin();
+        for text in [
+            include_str!("../scenarios/dice.toml"),
+            include_str!("../scenarios/skirmish.toml"),
+        ] {
+            let conflict = Scenario::from_toml(text, &catalog).unwrap().conflict;
+            let strategy = SolverStrategy::IterativeDeepening(10);
+            let sequential =
+                Solver::engage_parallel(&conflict, strategy, &Personality::default(), 1);
+            let parallel = Solver::engage_parallel(&conflict, strategy, &Personality::default(), 4);
+            assert_eq!(parallel.outcome, sequential.outcome);
+            assert_eq!(parallel.search_depth, sequential.search_depth);
+        }
+    }
+
+    #[test]
+    fn move_ordering_prunes_more_without_changing_the_outcome() {
+        let catalog = WeaponCatalog::builtin();
+        for text in [
+            include_str!("../scenarios/skirmish.toml"),
+            include_str!("../scenarios/effects.toml"),
+        ] {
+            let conflict = Scenario::from_toml(text, &catalog).unwrap().conflict;
+            let search = |mut ordering| {
+                let table = TranspositionTable::default();
+                Solver::deepen(
+                    &conflict,
+                    10,
+                    None,
+                    &table,
+                    &Personality::default(),
+                    1,
+                    &mut ordering,
+                )
+            };
+
+            let unordered = search(MoveOrdering::unordered());
+            let ordered = search(MoveOrdering::default());
+            assert_eq!(ordered.outcome, unordered.outcome);
+            assert_eq!(ordered.search_depth, unordered.search_depth);
+            assert!(ordered.evaluations < unordered.evaluations);
+            assert!(ordered.cuts < unordered.cuts);
+        }
+    }
+
+    #[test]
+    fn initiative_order_works() {
+        let mut heroes = build_default_hero_party(false, 30.0);
+        heroes.members[0].speed = 10;
+
+        let build_villains = |speed| Party {
+            id: 1,
+            members: (0..4)
+                .map(|id| PartyMember {
+                    id,
+                    health: 10.0,
+                    max_health: 10.0,
+                    damage_taken: 0.0,
+                    damage_mitigated: 0.0,
+                    weapon: Weapon {
+                        name: "a stick".to_string(),
+                        damage: 4.0,
+                        ..Default::default()
+                    },
+                    can_act: true,
+                    speed,
+                    row: Row::Front,
+                    stance: None,
+                    escaped: false,
+                    armor: 0.0,
+                    resistances: Vec::default(),
+                    damage_bonus: 0.0,
+                    abilities: Vec::default(),
+                    mana: ResourcePool::default(),
+                    stamina: ResourcePool::default(),
+                    spells: Vec::default(),
+                    effects: Vec::default(),
+                })
+                .collect(),
+            can_retreat: false,
+            retreated: false,
+            alliance: 1,
+            escape_chance: None,
+            personality: None,
+        };
+
+        // The fast hero strikes first in every round, so each round one villain
+        // less gets to act: 3 + 2 + 1 villain strikes over four rounds.
+        let conflict = Conflict {
+            parties: vec![heroes.clone(), build_villains(1)],
+            turn_order: TurnOrder::Initiative { position: 0 },
+            formations: false,
+        };
+
+        let solution = Solver::engage(
+            &conflict,
+            SolverStrategy::DepthLimited(20),
+            &Personality::default(),
+        );
+        assert_eq!(solution.outcome, OutcomeType::Win(6.0));
+        assert_eq!(solution.len(), 10);
+        assert!(solution.timeline[0].is_initiator_turn);
+        assert!(!solution.timeline[1].is_initiator_turn);
+
+        // If the villains are faster, all four strike before the hero can act.
+        let conflict = Conflict {
+            parties: vec![heroes, build_villains(20)],
+            turn_order: TurnOrder::Initiative { position: 0 },
+            formations: false,
+        };
+
+        let solution = Solver::engage(
+            &conflict,
+            SolverStrategy::DepthLimited(20),
+            &Personality::default(),
+        );
+        assert!(matches!(solution.outcome, OutcomeType::Lose(_)));
+        assert!(!solution.timeline[0].is_initiator_turn);
+    }
+
+    #[test]
+    fn time_limited_search_works() {
+        let conflict = Conflict {
+            parties: vec![
+                build_default_hero_party(true, 20.0),
+                build_complex_villain_party(false, 10.0),
+            ],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
+        };
+
+        // Without any time to spare, only the first iteration completes.
+        let solution = Solver::engage(
+            &conflict,
+            SolverStrategy::TimeLimited(Duration::ZERO),
+            &Personality::default(),
+        );
+        assert_eq!(solution.search_depth, 1);
+        assert!(solution.depth_limited);
+        assert!(matches!(solution.outcome, OutcomeType::Unknown(_)));
+
+        // With enough time, the search deepens until the conflict is resolved.
+        let solution = Solver::engage(
+            &conflict,
+            SolverStrategy::TimeLimited(Duration::from_secs(60)),
+            &Personality::default(),
+        );
+        assert_eq!(solution.outcome, OutcomeType::Win(10.0));
+        assert_eq!(solution.search_depth, 5);
+        assert_eq!(solution.len(), 5);
+    }
+
+    #[test]
+    fn won_duel_is_certain() {
+        let catalog = WeaponCatalog::builtin();
+        let scenario = Scenario::from_json(include_str!("../scenarios/duel.json"), &catalog);
+        let conflict = scenario.unwrap().conflict;
+
+        // The final blow lands exactly at the search horizon of the last iteration.
+        let solution = Solver::engage(
+            &conflict,
+            SolverStrategy::IterativeDeepening(10),
+            &Personality::default(),
+        );
+        assert_eq!(solution.outcome, OutcomeType::Win(5.0));
+        assert_eq!(solution.win_probability, 1.0);
+    }
+
+    #[test]
+    fn chance_nodes_yield_expected_value() {
+        // The hero hits half of the time and wins, or misses and is struck down.
+        let mut heroes = build_default_hero_party(false, 10.0);
+        heroes.members[0].weapon = Weapon {
+            name: "a stick".to_string(),
+            damage: 10.0,
+            hit_chance: 0.5,
+            ..Default::default()
+        };
+
+        let mut villains = build_default_hero_party(false, 10.0);
+        villains.id = 1;
+        villains.alliance = 1;
+
+        let conflict = Conflict {
+            parties: vec![heroes, villains],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
+        };
+
+        for strategy in [
+            SolverStrategy::DepthLimited(10),
+            SolverStrategy::IterativeDeepening(10),
+        ] {
+            let solution = Solver::engage(&conflict, strategy, &Personality::default());
+            assert_eq!(solution.outcome, OutcomeType::Expected(0.0));
+            assert_eq!(solution.win_probability, 0.5);
+
+            // The timeline follows the most likely result, hits first on ties.
+            assert_eq!(solution.len(), 1);
+            assert_eq!(solution.timeline[0].probability, 0.5);
+            assert!(solution.timeline[0].state.parties[1].is_defeated());
+        }
+    }
+
+    #[test]
+    fn healing_turns_the_tide() {
+        let mut heroes = build_default_hero_party(false, 10.0);
+        heroes.members[0].speed = 2;
+        heroes.members.push(PartyMember {
+            id: 1,
+            weapon: Weapon {
+                name: "their fists".to_string(),
+                damage: 1.0,
+                ..Default::default()
+            },
+            speed: 1,
+            ..heroes.members[0].clone()
+        });
+
+        let mut villains = build_simple_villain_party();
+        villains.members[0].health = 40.0;
+        villains.members[0].max_health = 40.0;
+        villains.members[0].weapon = Weapon {
+            name: "a stick".to_string(),
+            damage: 8.0,
+            ..Default::default()
+        };
+
+        // Without healing, the fighter falls after the second round.
+        let conflict = Conflict {
+            parties: vec![heroes.clone(), villains.clone()],
+            turn_order: TurnOrder::Initiative { position: 0 },
+            formations: false,
+        };
+
+        let solution = Solver::engage(
+            &conflict,
+            SolverStrategy::DepthLimited(20),
+            &Personality::default(),
+        );
+        assert!(matches!(solution.outcome, OutcomeType::Lose(_)));
+
+        // The healer keeps everyone standing instead of joining the fight.
+        heroes.members[1].abilities = vec![Action::Heal(HealAction { amount: 10.0 }).into()];
+        let conflict = Conflict {
+            parties: vec![heroes, villains],
+            turn_order: TurnOrder::Initiative { position: 0 },
+            formations: false,
+        };
+
+        let solution = Solver::engage(
+            &conflict,
+            SolverStrategy::DepthLimited(20),
+            &Personality::default(),
+        );
+        assert!(matches!(solution.outcome, OutcomeType::Win(_)));
+        assert!(solution.timeline.iter().any(|event| matches!(
+            &event.action,
+            AppliedAction::Targeted(TargetedAction {
+                action: Action::Heal(_),
+                ..
+            })
+        )));
+    }
+
+    #[test]
+    fn stunned_opponents_pass() {
+        let mut heroes = build_default_hero_party(false, 10.0);
+        let mut villains = build_default_hero_party(false, 20.0);
+        villains.id = 1;
+        villains.alliance = 1;
+
+        // Trading blows, the villain strikes down the hero first.
+        let conflict = Conflict {
+            parties: vec![heroes.clone(), villains.clone()],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
+        };
+
+        let solution = Solver::engage(
+            &conflict,
+            SolverStrategy::DepthLimited(10),
+            &Personality::default(),
+        );
+        assert!(matches!(solution.outcome, OutcomeType::Lose(_)));
+
+        // Stunning the villain first buys the hero two free strikes.
+        heroes.members[0].abilities = vec![Action::StatusEffect(StatusEffect {
+            kind: StatusEffectKind::Stun,
+            duration: 2,
+        })
+        .into()];
+        let conflict = Conflict {
+            parties: vec![heroes, villains],
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
+        };
+
+        let solution = Solver::engage(
+            &conflict,
+            SolverStrategy::DepthLimited(10),
+            &Personality::default(),
+        );
+        assert_eq!(solution.outcome, OutcomeType::Win(10.0));
+        assert_eq!(solution.timeline[1].action, AppliedAction::Pass);
+    }
+
+    #[test]
+    fn every_other_party_is_an_opponent() {
+        let heroes = build_default_hero_party(false, 30.0);
+        let villains: Vec<_> = (1..=2)
+            .map(|id| {
+                let mut party = build_default_hero_party(false, 10.0);
+                party.id = id;
+                party.alliance = id;
+                party
+            })
+            .collect();
+
+        // The hero strikes down one villain after the other, taking a single hit in between.
+        let conflict = Conflict {
+            parties: [vec![heroes], villains].concat(),
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
+        };
+
+        let solution = Solver::engage(
+            &conflict,
+            SolverStrategy::DepthLimited(10),
+            &Personality::default(),
+        );
+        assert_eq!(solution.outcome, OutcomeType::Win(20.0));
+        assert_eq!(solution.len(), 3);
+        assert_eq!(solution.timeline[1].party_id, 2);
+        assert_eq!(
+            solution
+                .results
+                .iter()
+                .map(|r| r.outcome)
+                .collect::<Vec<_>>(),
+            vec![
+                PartyOutcome::Victorious,
+                PartyOutcome::Defeated,
+                PartyOutcome::Defeated
+            ]
+        );
+    }
+
+    #[test]
+    fn parties_follow_their_own_preferences() {
+        let heroes = build_default_hero_party(false, 30.0);
+        let villains: Vec<_> = (1..=2)
+            .map(|id| {
+                let mut party = build_default_hero_party(true, 20.0);
+                party.id = id;
+                party.alliance = id;
+                party
+            })
+            .collect();
+
+        let mut conflict = Conflict {
+            parties: [vec![heroes], villains].concat(),
+            turn_order: TurnOrder::Alternating { party: 0 },
+            formations: false,
+        };
+
+        // Assuming the worst, both villains gang up on the hero.
+        let solution = Solver::engage(
+            &conflict,
+            SolverStrategy::DepthLimited(10),
+            &Personality::default(),
+        );
+        assert!(matches!(solution.outcome, OutcomeType::Lose(_)));
+
+        // Caring only for themselves, each villain fears to be struck down by the other
+        // and runs, leaving the hero behind.
+        for party in conflict.parties[1..].iter_mut() {
+            party.personality = Some(Personality::default());
+        }
+
+        let solution = Solver::engage(
+            &conflict,
+            SolverStrategy::IterativeDeepening(10),
+            &Personality::default(),
+        );
+        assert!(matches!(solution.outcome, OutcomeType::Remain(_)));
+        assert_eq!(
+            solution
+                .results
+                .iter()
+                .map(|r| r.outcome)
+                .collect::<Vec<_>>(),
+            vec![
+                PartyOutcome::Victorious,
+                PartyOutcome::Retreated,
+                PartyOutcome::Retreated
+            ]
+        );
     }
 
     fn build_default_hero_party(can_retreat: bool, health: f32) -> Party {
-        let heroes = Party {
+        Party {
             id: 0,
             members: vec![PartyMember {
                 id: 0,
                 health,
+                max_health: health,
                 damage_taken: 0.0,
-                weapon: Weapon::Fists(Fists { damage: 10.0 }),
+                damage_mitigated: 0.0,
+                weapon: Weapon {
+                    name: "their fists".to_string(),
+                    damage: 10.0,
+                    ..Default::default()
+                },
                 can_act: true,
+                speed: 0,
+                row: Row::Front,
+                stance: None,
+                escaped: false,
+                armor: 0.0,
+                resistances: Vec::default(),
+                damage_bonus: 0.0,
+                abilities: Vec::default(),
+                mana: ResourcePool::default(),
+                stamina: ResourcePool::default(),
+                spells: Vec::default(),
+                effects: Vec::default(),
             }],
             can_retreat,
             retreated: false,
-        };
-        heroes
+            alliance: 0,
+            escape_chance: None,
+            personality: None,
+        }
     }
 
     fn build_complex_villain_party(can_retreat: bool, dangerous_health: f32) -> Party {
-        let villains = Party {
+        Party {
             id: 1,
             members: vec![
                 PartyMember {
                     id: 0,
                     health: 15.0,
+                    max_health: 15.0,
                     damage_taken: 0.0,
-                    weapon: Weapon::Stick(Stick { damage: 5.0 }),
+                    damage_mitigated: 0.0,
+                    weapon: Weapon {
+                        name: "a stick".to_string(),
+                        damage: 5.0,
+                        ..Default::default()
+                    },
                     can_act: true,
+                    speed: 0,
+                    row: Row::Front,
+                    stance: None,
+                    escaped: false,
+                    armor: 0.0,
+                    resistances: Vec::default(),
+                    damage_bonus: 0.0,
+                    abilities: Vec::default(),
+                    mana: ResourcePool::default(),
+                    stamina: ResourcePool::default(),
+                    spells: Vec::default(),
+                    effects: Vec::default(),
                 },
                 PartyMember {
                     id: 1,
                     health: dangerous_health,
+                    max_health: dangerous_health,
                     damage_taken: 0.0,
-                    weapon: Weapon::Fists(Fists {
+                    damage_mitigated: 0.0,
+                    weapon: Weapon {
+                        name: "their fists".to_string(),
                         damage: 20.0, // 👈 may defeat hero in one hit
-                    }),
+                        ..Default::default()
+                    },
                     can_act: true,
+                    speed: 0,
+                    row: Row::Front,
+                    stance: None,
+                    escaped: false,
+                    armor: 0.0,
+                    resistances: Vec::default(),
+                    damage_bonus: 0.0,
+                    abilities: Vec::default(),
+                    mana: ResourcePool::default(),
+                    stamina: ResourcePool::default(),
+                    spells: Vec::default(),
+                    effects: Vec::default(),
                 },
             ],
             can_retreat,
             retreated: false,
-        };
-        villains
+            alliance: 1,
+            escape_chance: None,
+            personality: None,
+        }
     }
 
     fn build_simple_villain_party() -> Party {
-        let villains = Party {
+        Party {
             id: 1,
             members: vec![PartyMember {
                 id: 0,
                 health: 25.0,
+                max_health: 25.0,
                 damage_taken: 0.0,
-                weapon: Weapon::Stick(Stick { damage: 10.0 }),
+                damage_mitigated: 0.0,
+                weapon: Weapon {
+                    name: "a stick".to_string(),
+                    damage: 10.0,
+                    ..Default::default()
+                },
                 can_act: true,
+                speed: 0,
+                row: Row::Front,
+                stance: None,
+                escaped: false,
+                armor: 0.0,
+                resistances: Vec::default(),
+                damage_bonus: 0.0,
+                abilities: Vec::default(),
+                mana: ResourcePool::default(),
+                stamina: ResourcePool::default(),
+                spells: Vec::default(),
+                effects: Vec::default(),
             }],
             can_retreat: false,
             retreated: false,
-        };
-        villains
+            alliance: 1,
+            escape_chance: None,
+            personality: None,
+        }
     }
 
     #[test]
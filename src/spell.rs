@@ -0,0 +1,54 @@
+use crate::action::{Action, Cost, SpellAction};
+use crate::weapon::WeaponArea;
+use serde::Serialize;
+use std::hash::{Hash, Hasher};
+
+/// A spell a party member is able to cast, e.g. a fireball.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Spell {
+    /// The name of the spell, unique among the spells of a member.
+    pub name: String,
+    /// The effect of the spell on each of its targets.
+    pub action: Action,
+    /// The resources spent on every cast.
+    pub cost: Cost,
+    /// The members affected by a single cast. Whether these are allies or
+    /// opponents depends on the effect, see [`Action::targets_allies`].
+    pub area: WeaponArea,
+    /// The number of turns of the caster after a cast before the spell
+    /// can be cast again.
+    pub cooldown: u32,
+    /// The number of turns of the caster remaining until the spell can be cast again.
+    pub ready_in: u32,
+}
+
+impl Spell {
+    /// Determines whether the spell is off cooldown.
+    pub fn is_ready(&self) -> bool {
+        self.ready_in == 0
+    }
+
+    /// Creates the action of casting the spell.
+    pub fn cast(&self) -> Action {
+        Action::Spell(SpellAction {
+            name: self.name.clone(),
+            effect: Box::new(self.action.clone()),
+            area: self.area,
+        })
+    }
+}
+
+/// Spells compare equal if all of their fields are equal;
+/// amounts are never `NaN`.
+impl Eq for Spell {}
+
+impl Hash for Spell {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.action.hash(state);
+        self.cost.hash(state);
+        self.area.hash(state);
+        self.cooldown.hash(state);
+        self.ready_in.hash(state);
+    }
+}
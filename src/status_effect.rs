@@ -0,0 +1,102 @@
+use serde::Serialize;
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+
+/// A status effect affecting a party member for a number of turns.
+#[derive(Clone, PartialEq, Serialize)]
+pub struct StatusEffect {
+    /// The kind of effect.
+    pub kind: StatusEffectKind,
+    /// The number of turns of the affected member the effect remains active for.
+    /// Effects wear off at the end of the member's turn.
+    pub duration: u32,
+}
+
+/// The kind of a status effect.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StatusEffectKind {
+    /// Deals damage at the end of every turn.
+    Poison {
+        /// The damage dealt per turn.
+        damage: f32,
+    },
+    /// Restores health at the end of every turn, up to the maximum health.
+    Regen {
+        /// The amount of health restored per turn.
+        amount: f32,
+    },
+    /// Prevents the member from acting.
+    Stun,
+    /// Prevents the member from acting until it wears off or the member takes damage.
+    Sleep,
+}
+
+impl StatusEffect {
+    /// Determines whether the effect is good for the affected member,
+    /// i.e. whether it is applied to allies rather than opponents.
+    pub fn is_beneficial(&self) -> bool {
+        matches!(self.kind, StatusEffectKind::Regen { .. })
+    }
+
+    /// Determines whether the effect prevents the affected member from acting.
+    pub fn is_incapacitating(&self) -> bool {
+        matches!(self.kind, StatusEffectKind::Stun | StatusEffectKind::Sleep)
+    }
+
+    /// Determines whether both effects are of the same kind, regardless of their
+    /// strength or duration. Effects of the same kind do not stack.
+    pub fn is_same_kind(&self, other: &StatusEffect) -> bool {
+        std::mem::discriminant(&self.kind) == std::mem::discriminant(&other.kind)
+    }
+}
+
+/// Status effects compare equal if all of their fields are equal;
+/// amounts are never `NaN`.
+impl Eq for StatusEffect {}
+
+impl Hash for StatusEffect {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(&self.kind).hash(state);
+        match self.kind {
+            StatusEffectKind::Poison { damage } => damage.to_bits().hash(state),
+            StatusEffectKind::Regen { amount } => amount.to_bits().hash(state),
+            StatusEffectKind::Stun | StatusEffectKind::Sleep => {}
+        }
+        self.duration.hash(state);
+    }
+}
+
+impl Debug for StatusEffect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let turns = if self.duration == 1 { "turn" } else { "turns" };
+        match self.kind {
+            StatusEffectKind::Poison { damage } => write!(
+                f,
+                "poison for {damage} damage per turn over {} {turns}",
+                self.duration
+            ),
+            StatusEffectKind::Regen { amount } => write!(
+                f,
+                "regenerate {amount} health per turn over {} {turns}",
+                self.duration
+            ),
+            StatusEffectKind::Stun => write!(f, "stun for {} {turns}", self.duration),
+            StatusEffectKind::Sleep => write!(f, "put to sleep for {} {turns}", self.duration),
+        }
+    }
+}
+
+/// Describes the state of the affected member, e.g. "stunned for 1 turn".
+impl Display for StatusEffect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let turns = if self.duration == 1 { "turn" } else { "turns" };
+        let state = match self.kind {
+            StatusEffectKind::Poison { .. } => "poisoned",
+            StatusEffectKind::Regen { .. } => "regenerating",
+            StatusEffectKind::Stun => "stunned",
+            StatusEffectKind::Sleep => "asleep",
+        };
+        write!(f, "{state} for {} {turns}", self.duration)
+    }
+}
@@ -0,0 +1,187 @@
+use crate::action::AppliedAction;
+use crate::conflict::Conflict;
+use crate::value::TerminalState;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, BuildHasherDefault};
+use std::sync::{Mutex, MutexGuard};
+
+/// Describes how a stored value relates to the true minimax value of a state.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Bound {
+    /// The stored value is the exact minimax value.
+    Exact,
+    /// The true value is at least the stored value, i.e. the search failed high.
+    Lower,
+    /// The true value is at most the stored value, i.e. the search failed low.
+    Upper,
+    /// The stored value does not bound the true value; only the best action is of use.
+    Unbounded,
+}
+
+/// A stored search result.
+#[derive(Debug, Clone)]
+pub struct TranspositionEntry {
+    /// The remaining search depth below the state when the value was determined.
+    /// Is [`TranspositionEntry::UNLIMITED`] if the search never hit the depth limit,
+    /// i.e. the value is independent of the search depth.
+    pub draft: usize,
+    /// The value found for the state.
+    pub value: TerminalState,
+    /// How `value` relates to the true value of the state.
+    pub bound: Bound,
+    /// The best action found for the state, if any.
+    pub best_action: Option<AppliedAction>,
+    /// The probability of the initiating party winning from the state.
+    pub win_probability: f32,
+}
+
+/// A transposition table caching search results for states that can be
+/// reached through different sequences of actions, as well as across
+/// iterations of iterative deepening. The table may be shared by the
+/// threads of a parallel search; its entries are spread over shards with
+/// locks of their own, so that threads rarely wait for each other.
+#[derive(Debug)]
+pub struct TranspositionTable {
+    /// The shards; a state always lives in the shard selected by its hash.
+    shards: Box<[Mutex<Shard>]>,
+}
+
+/// The entries of a shard by state, for the minimizing and the maximizing side to move.
+/// Keeping the sides apart lets lookups borrow the state instead of building a key.
+type Shard = [HashMap<Conflict, TranspositionEntry>; 2];
+
+impl TranspositionEntry {
+    /// The draft of values that do not depend on the search depth.
+    pub const UNLIMITED: usize = usize::MAX;
+
+    /// Determines whether the entry can be used in place of searching a state.
+    ///
+    /// ## Arguments
+    /// * `draft` - The remaining search depth below the state.
+    /// * `alpha` - The lower bound of the search window.
+    /// * `beta` - The upper bound of the search window.
+    ///
+    /// ## Returns
+    /// The value to use for the state, or [`None`] if the state needs to be searched.
+    pub fn cutoff(&self, draft: usize, alpha: f32, beta: f32) -> Option<TerminalState> {
+        if self.draft < draft {
+            return None;
+        }
+
+        match self.bound {
+            Bound::Exact => Some(self.value),
+            Bound::Lower if self.value.value() >= beta => Some(self.value),
+            Bound::Upper if self.value.value() <= alpha => Some(self.value),
+            _ => None,
+        }
+    }
+}
+
+impl TranspositionTable {
+    /// The number of shards; comfortably more than the number of threads searching.
+    const SHARDS: usize = 64;
+
+    /// Looks up the entry for a state.
+    ///
+    /// ## Arguments
+    /// * `state` - The state of the conflict.
+    /// * `is_maximizing` - Whether the initiating party is to move.
+    pub fn probe(&self, state: &Conflict, is_maximizing: bool) -> Option<TranspositionEntry> {
+        self.shard(state)[usize::from(is_maximizing)]
+            .get(state)
+            .cloned()
+    }
+
+    /// Stores an entry for a state. Existing entries are only replaced
+    /// by entries of at least the same draft.
+    ///
+    /// ## Arguments
+    /// * `state` - The state of the conflict.
+    /// * `is_maximizing` - Whether the initiating party is to move.
+    /// * `entry` - The search result to store.
+    pub fn store(&self, state: &Conflict, is_maximizing: bool, entry: TranspositionEntry) {
+        let entries = &mut self.shard(state)[usize::from(is_maximizing)];
+        match entries.get(state) {
+            Some(existing) if existing.draft > entry.draft => {}
+            _ => {
+                entries.insert(state.clone(), entry);
+            }
+        }
+    }
+
+    /// Returns the number of stored entries.
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| Self::lock(shard).iter().map(HashMap::len).sum::<usize>())
+            .sum()
+    }
+
+    /// Locks the shard holding a state for the calling thread.
+    fn shard(&self, state: &Conflict) -> MutexGuard<'_, Shard> {
+        let hash = BuildHasherDefault::<DefaultHasher>::default().hash_one(state);
+        Self::lock(&self.shards[hash as usize % self.shards.len()])
+    }
+
+    /// Locks a shard for the calling thread.
+    fn lock(shard: &Mutex<Shard>) -> MutexGuard<'_, Shard> {
+        shard
+            .lock()
+            .expect("No thread panics while holding the table")
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self {
+            shards: (0..Self::SHARDS).map(|_| Mutex::default()).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(draft: usize, value: f32, bound: Bound) -> TranspositionEntry {
+        TranspositionEntry {
+            draft,
+            value: TerminalState::Heuristic(value),
+            bound,
+            best_action: None,
+            win_probability: 0.0,
+        }
+    }
+
+    #[test]
+    fn exact_entries_cut_within_draft() {
+        let entry = entry(3, 5.0, Bound::Exact);
+        assert_eq!(
+            entry.cutoff(3, 0.0, 10.0),
+            Some(TerminalState::Heuristic(5.0))
+        );
+        assert_eq!(
+            entry.cutoff(2, 0.0, 10.0),
+            Some(TerminalState::Heuristic(5.0))
+        );
+        assert_eq!(entry.cutoff(4, 0.0, 10.0), None);
+    }
+
+    #[test]
+    fn bounded_entries_respect_window() {
+        let lower = entry(3, 5.0, Bound::Lower);
+        assert_eq!(lower.cutoff(3, 0.0, 10.0), None);
+        assert_eq!(
+            lower.cutoff(3, 0.0, 5.0),
+            Some(TerminalState::Heuristic(5.0))
+        );
+
+        let upper = entry(3, 5.0, Bound::Upper);
+        assert_eq!(upper.cutoff(3, 0.0, 10.0), None);
+        assert_eq!(
+            upper.cutoff(3, 5.0, 10.0),
+            Some(TerminalState::Heuristic(5.0))
+        );
+    }
+}
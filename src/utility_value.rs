@@ -1,50 +1,283 @@
+use crate::action::Action;
 use crate::conflict::Conflict;
+use crate::party::Party;
+use crate::party_member::PartyMember;
+use crate::status_effect::{StatusEffect, StatusEffectKind};
 use crate::value::TerminalState;
+use serde::Serialize;
 
-/// Gets the utility of the current node
-pub fn get_utility(state: &Conflict) -> TerminalState {
-    if state.initiator.is_defeated() || state.initiator.has_retreated() {
+/// Bounds on the utility of all states reachable from a given state.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct UtilityBounds {
+    /// No reachable state has a lower utility.
+    pub lower: f32,
+    /// No reachable state has a higher utility.
+    pub upper: f32,
+}
+
+/// Evaluates states of a conflict in the eyes of one of its parties; the zero-sum search
+/// evaluates every state for the initiating party, i.e. the maximizing player.
+/// Evaluators give the solver a personality, e.g. by preferring damage dealt over health kept.
+///
+/// Defeats and retreats must have a negative utility, and every other state a positive one:
+/// the search relies on the sign to detect defeats early. Evaluators are shared
+/// among the threads of a parallel search.
+pub trait Evaluator: Sync {
+    /// Gets the utility of a state for a party.
+    ///
+    /// ## Arguments
+    /// * `state` - The state to evaluate.
+    /// * `party_id` - The ID of the party whose utility to determine.
+    fn utility(&self, state: &Conflict, party_id: usize) -> TerminalState;
+
+    /// Determines bounds on a party's utility of all states reachable from the current state
+    /// within a limited number of actions. Both bounds must be finite.
+    ///
+    /// ## Arguments
+    /// * `state` - The current state of the conflict.
+    /// * `party_id` - The ID of the party whose utility to bound.
+    /// * `max_actions` - The maximum number of actions taken from the current state.
+    fn bounds(&self, state: &Conflict, party_id: usize, max_actions: usize) -> UtilityBounds;
+}
+
+/// An evaluator scoring states by the health a party keeps, with weights
+/// for outcomes short of a win or defeat. The [`Default`] is the solver's standard behavior.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct Personality {
+    /// The factor applied to the damage taken when retreating, compared to a defeat.
+    /// Members who died before the retreat always weigh fully.
+    pub retreat: f32,
+    /// The factor applied to the health kept when all opponents retreated, compared to a win.
+    pub remain: f32,
+    /// The factor applied to the health kept while the conflict is undecided, compared to a win.
+    pub heuristic: f32,
+    /// The utility of every point of health the hostile parties lost,
+    /// in addition to the health kept.
+    pub aggression: f32,
+}
+
+impl Default for Personality {
+    fn default() -> Self {
+        Self {
+            retreat: 0.1,
+            remain: 0.1,
+            heuristic: 0.1,
+            aggression: 0.0,
+        }
+    }
+}
+
+impl Personality {
+    /// A personality valuing damage dealt to the opponents as much as health kept.
+    pub fn aggressive() -> Self {
+        Self {
+            aggression: 1.0,
+            ..Self::default()
+        }
+    }
+
+    /// A personality preserving health, happy to retreat or to see the opponents leave.
+    pub fn cautious() -> Self {
+        Self {
+            retreat: 0.02,
+            remain: 0.5,
+            ..Self::default()
+        }
+    }
+
+    /// Gets the largest factor applied to the utility of any outcome.
+    fn max_factor(&self) -> f32 {
+        [self.retreat, self.remain, self.heuristic]
+            .into_iter()
+            .fold(1.0, f32::max)
+    }
+}
+
+impl Evaluator for Personality {
+    fn utility(&self, state: &Conflict, party_id: usize) -> TerminalState {
+        get_utility(self, state, state.action_target(party_id))
+    }
+
+    fn bounds(&self, state: &Conflict, party_id: usize, max_actions: usize) -> UtilityBounds {
+        get_utility_bounds(self, state, state.action_target(party_id), max_actions)
+    }
+}
+
+/// Gets the utility of the current node for a party
+fn get_utility(personality: &Personality, state: &Conflict, party: &Party) -> TerminalState {
+    if party.is_defeated() || party.has_retreated() {
         // The current party being dead is a terminal state and always is a negative reward.
         // We sum up the total damage taken to punish strong defeats
         // harder than slight defeats.
-        let utility = state
-            .initiator
+        if party.is_defeated() {
+            let utility = party.members.iter().map(|m| -m.damage_taken).sum();
+            debug_assert!(utility <= 0.0);
+            return TerminalState::Defeat(utility);
+        }
+
+        // Retreating is only a fraction as bad, at least for the members who got away.
+        // Members left behind dead weigh just as heavily as in a defeat.
+        let utility = party
             .members
             .iter()
-            .map(|m| -m.damage_taken)
+            .map(|m| {
+                if m.is_dead() {
+                    -m.damage_taken
+                } else {
+                    -m.damage_taken * personality.retreat
+                }
+            })
             .sum();
         debug_assert!(utility <= 0.0);
-        return if state.initiator.is_defeated() {
-            TerminalState::Defeat(utility)
-        } else {
-            TerminalState::Retreat(utility * 0.1)
-        };
+        return TerminalState::Retreat(utility);
     }
 
     // As a naive choice, we simply sum up the health of each member.
     // This is to ensure we play less risky and don't need to heal as much.
     // Health can never be negative, but to be sure we cap it at zero.
     //
-    // In theory we can also factor in the damage taken by the opponent
+    // In theory we can also factor in the damage taken by the opponents
     // as dealing more damage could be useful. Whether or not that is a
     // useful idea depends on the remaining game mechanics (say, e.g., a massive
     // magical effect that takes a day to recover vs. death by a slap with a stick).
-    let utility = state
-        .initiator
+    //
+    // Members who got away on their own are safe, but no longer stand with their party;
+    // just like after a retreat, their health only counts for a fraction.
+    let utility = party
         .members
         .iter()
-        .map(|m| m.health.max(0.0))
-        .sum();
+        .map(|m| {
+            let health = m.health.max(0.0);
+            if m.escaped {
+                health * personality.retreat
+            } else {
+                health
+            }
+        })
+        .sum::<f32>()
+        + personality.aggression * health_lost(state.parties_hostile_to(party));
     debug_assert!(utility > 0.0);
 
-    if state.opponent.is_defeated() {
+    if state.parties_hostile_to(party).all(Party::is_defeated) {
         TerminalState::Win(utility)
-    } else if state.opponent.has_retreated() {
+    } else if !state.parties_hostile_to(party).any(Party::is_engaged) {
         // This is a somewhat delicate balancing. If the utility
         // value for a remain is equal to a win, the opposing party
-        // changes their preferences.
-        TerminalState::Remain(utility * 0.1)
+        // parties change their preferences.
+        TerminalState::Remain(utility * personality.remain)
     } else {
-        TerminalState::Heuristic(utility * 0.1)
+        TerminalState::Heuristic(utility * personality.heuristic)
+    }
+}
+
+/// Determines bounds on the utility of all states reachable from the current state
+/// within a limited number of actions. Both bounds are always finite.
+fn get_utility_bounds(
+    personality: &Personality,
+    state: &Conflict,
+    party: &Party,
+    max_actions: usize,
+) -> UtilityBounds {
+    // Heals never exceed the maximum health, so a win at full health is the best case,
+    // unless, of course, the opponents lose all of their health as well.
+    let health: f32 = party
+        .members
+        .iter()
+        .map(|m| m.health.max(m.max_health).max(0.0))
+        .sum();
+    let max_health_lost: f32 = state
+        .parties_hostile_to(party)
+        .flat_map(|party| &party.members)
+        .map(|m| m.health.max(m.max_health).max(0.0))
+        .sum();
+    let upper = (health + personality.aggression * max_health_lost) * personality.max_factor();
+
+    // The worst case is a defeat after every action dealt the highest possible damage,
+    // assuming the opponents' strongest buff is already in effect, every hit lands on the
+    // most vulnerable member (or as many members as an area attack can hit), every member
+    // suffers from the strongest poison after every action, and resources and spells are
+    // always available.
+    // Armor and resistances only ever reduce damage.
+    let damage_taken: f32 = party.members.iter().map(|m| m.damage_taken).sum();
+    let max_bonus = state
+        .parties_hostile_to(party)
+        .flat_map(|party| &party.members)
+        .flat_map(supporting_actions)
+        .filter_map(|action| match action {
+            Action::Buff(buff) => Some(buff.damage_bonus),
+            _ => None,
+        })
+        .fold(0.0, f32::max);
+    let max_damage = state
+        .parties_hostile_to(party)
+        .flat_map(|party| &party.members)
+        .flat_map(|m| {
+            let mut member = m.clone();
+            member.damage_bonus = member.damage_bonus.max(max_bonus);
+            member.mana.refill();
+            member.stamina.refill();
+            for spell in member.spells.iter_mut() {
+                spell.ready_in = 0;
+            }
+            member.actions()
+        })
+        .map(|ability| {
+            let targets = ability.action.area().max_targets(party.members.len());
+            ability.action.max_damage() * targets as f32
+        })
+        .fold(0.0, f32::max);
+    let vulnerability = party
+        .members
+        .iter()
+        .flat_map(|m| &m.resistances)
+        .map(|r| r.multiplier)
+        .fold(1.0, f32::max);
+    let max_poison = state
+        .parties_hostile_to(party)
+        .flat_map(|party| &party.members)
+        .flat_map(supporting_actions)
+        .filter_map(|action| match action {
+            Action::StatusEffect(effect) => poison_damage(effect),
+            _ => None,
+        })
+        .fold(0.0, f32::max);
+    let poison: f32 = party
+        .members
+        .iter()
+        .map(|m| {
+            m.effects
+                .iter()
+                .filter_map(poison_damage)
+                .fold(max_poison, f32::max)
+        })
+        .sum();
+
+    let lower = -(damage_taken + max_actions as f32 * (max_damage * vulnerability + poison));
+    UtilityBounds {
+        lower: lower * personality.max_factor(),
+        upper,
+    }
+}
+
+/// Sums up the health the members of the parties lost, up to their maximum health.
+fn health_lost<'a>(parties: impl Iterator<Item = &'a Party>) -> f32 {
+    parties
+        .flat_map(|party| &party.members)
+        .map(|m| (m.max_health - m.health).max(0.0))
+        .sum()
+}
+
+/// Lists the actions a member may take besides attacking, i.e. abilities and spells.
+fn supporting_actions(member: &PartyMember) -> impl Iterator<Item = &Action> {
+    let abilities = member.abilities.iter().map(|ability| &ability.action);
+    let spells = member.spells.iter().map(|spell| spell.action.effect());
+    abilities.chain(spells)
+}
+
+/// Gets the damage a poison deals per turn, if the effect is a poison.
+fn poison_damage(effect: &StatusEffect) -> Option<f32> {
+    match effect.kind {
+        StatusEffectKind::Poison { damage } => Some(damage),
+        _ => None,
     }
 }
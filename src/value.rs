@@ -25,6 +25,8 @@ pub enum TerminalState {
     Retreat(f32),
     /// No clear decision can be made.
     Heuristic(f32),
+    /// The outcome depends on chance; the value is the expected utility.
+    Expected(f32),
     /// The branch is unexplored and has a default value.
     OpenUnexplored(f32),
 }
@@ -133,6 +135,7 @@ impl TerminalState {
             TerminalState::Remain(value) => *value,
             TerminalState::Retreat(value) => *value,
             TerminalState::Heuristic(value) => *value,
+            TerminalState::Expected(value) => *value,
             TerminalState::OpenUnexplored(value) => *value,
         }
     }
@@ -140,6 +143,16 @@ impl TerminalState {
     pub fn is_negative(&self) -> bool {
         self.value() < 0.0
     }
+
+    /// The probability of the initiating party winning from this state: certain for
+    /// a win, none otherwise. A heuristic value at the search horizon does not count
+    /// as a win yet, so probabilities derived from it are lower bounds.
+    pub fn win_probability(&self) -> f32 {
+        match self {
+            TerminalState::Win(_) => 1.0,
+            _ => 0.0,
+        }
+    }
 }
 
 impl Deref for TerminalState {
@@ -152,6 +165,7 @@ impl Deref for TerminalState {
             TerminalState::Remain(value) => value,
             TerminalState::Retreat(value) => value,
             TerminalState::Heuristic(value) => value,
+            TerminalState::Expected(value) => value,
             TerminalState::OpenUnexplored(value) => value,
         }
     }
@@ -165,6 +179,7 @@ impl Display for TerminalState {
             TerminalState::Remain(value) => write!(f, "remain({})", value),
             TerminalState::Retreat(value) => write!(f, "retreat({})", value),
             TerminalState::Heuristic(value) => write!(f, "H({})", value),
+            TerminalState::Expected(value) => write!(f, "E({})", value),
             TerminalState::OpenUnexplored(value) => write!(f, "O({})", value),
         }
     }
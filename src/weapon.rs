@@ -1,59 +1,281 @@
-use std::fmt::{Debug, Formatter};
+use crate::action::Cost;
+use crate::status_effect::StatusEffect;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
 
-/// A weapon to be used by someone.
-#[derive(Clone, PartialEq)]
-pub enum Weapon {
-    /// Fists it is.
-    Fists(Fists),
-    /// What is brown and sticky?
-    Stick(Stick),
+/// A weapon to be used by someone. Weapons are plain data, typically taken
+/// from a [`WeaponCatalog`](crate::catalog::WeaponCatalog).
+#[derive(Clone, PartialEq, Serialize)]
+pub struct Weapon {
+    /// The name of the weapon as used in a sentence, e.g. "a stick".
+    pub name: String,
+    /// The amount of damage dealt on a successful hit.
+    pub damage: f32,
+    /// The dice rolled for additional damage on a successful hit.
+    pub dice: Dice,
+    /// The kind of damage dealt.
+    pub damage_type: DamageType,
+    /// The distance at which the weapon can be used.
+    pub range: WeaponRange,
+    /// The opponents hit by a single attack.
+    pub area: WeaponArea,
+    /// The probability of hitting the target.
+    pub hit_chance: f32,
+    /// The probability of a hit being critical, dealing double damage.
+    pub critical_chance: f32,
+    /// The status effects applied to the target on a successful hit.
+    pub effects: Vec<StatusEffect>,
+    /// The resources spent on every attack, e.g. stamina for heavy swings.
+    pub cost: Cost,
 }
 
-impl Weapon {
-    /// Gets the damage of the weapon.
-    pub fn damage(&self) -> f32 {
+/// The kind of damage dealt by a weapon.
+#[derive(
+    Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum DamageType {
+    /// Blunt force, e.g. fists or clubs.
+    #[default]
+    Bludgeoning,
+    /// Stabs, e.g. daggers or arrows.
+    Piercing,
+    /// Cuts, e.g. swords or axes.
+    Slashing,
+    /// Burns.
+    Fire,
+    /// Frostbite.
+    Cold,
+    /// Electric shocks.
+    Lightning,
+}
+
+/// The distance at which a weapon can be used.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeaponRange {
+    /// Close combat only.
+    #[default]
+    Melee,
+    /// Usable from a distance, e.g. bows or spells.
+    Ranged,
+}
+
+/// The opponents hit by a single attack of a weapon.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeaponArea {
+    /// Only the selected target.
+    #[default]
+    Single,
+    /// The selected target and the members next to it, e.g. an axe swung in an arc.
+    Cleave,
+    /// Every member of the opposing party, e.g. a fireball.
+    All,
+}
+
+impl DamageType {
+    /// Determines whether the damage is physical, i.e. reduced by armor.
+    pub fn is_physical(&self) -> bool {
+        matches!(
+            self,
+            DamageType::Bludgeoning | DamageType::Piercing | DamageType::Slashing
+        )
+    }
+}
+
+impl WeaponArea {
+    /// Gets the highest number of members hit by a single attack.
+    ///
+    /// ## Arguments
+    /// * `party_size` - The number of members in the targeted party.
+    pub fn max_targets(&self, party_size: usize) -> usize {
         match self {
-            Weapon::Stick(ref w) => w.damage,
-            Weapon::Fists(ref w) => w.damage,
+            WeaponArea::Single => party_size.min(1),
+            WeaponArea::Cleave => party_size.min(3),
+            WeaponArea::All => party_size,
         }
     }
 }
 
-/// Fists. Not very effective.
-#[derive(Debug, Clone, PartialEq)]
-pub struct Fists {
-    /// The amount of damage dealt on a successful hit.
-    pub damage: f32,
+impl Weapon {
+    /// Determines whether every attack with the weapon deals the same damage.
+    pub fn is_deterministic(&self) -> bool {
+        self.dice.is_none() && self.hit_chance >= 1.0 && self.critical_chance <= 0.0
+    }
 }
 
-/// A simple stick. Not very effective.
-#[derive(Debug, Clone, PartialEq)]
-pub struct Stick {
-    /// The amount of damage dealt on a successful hit.
-    pub damage: f32,
+/// Weapons hit for their base damage unless configured otherwise.
+impl Default for Weapon {
+    fn default() -> Self {
+        Self {
+            name: String::default(),
+            damage: 0.0,
+            dice: Dice::NONE,
+            damage_type: DamageType::default(),
+            range: WeaponRange::default(),
+            area: WeaponArea::default(),
+            hit_chance: 1.0,
+            critical_chance: 0.0,
+            effects: Vec::default(),
+            cost: Cost::default(),
+        }
+    }
+}
+
+/// A number of equal dice, e.g. `2d6` for two six-sided dice.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Serialize)]
+pub struct Dice {
+    /// The number of dice rolled.
+    pub count: u32,
+    /// The number of sides of each die.
+    pub sides: u32,
+}
+
+impl Dice {
+    /// No dice at all; always rolls zero.
+    pub const NONE: Dice = Dice { count: 0, sides: 0 };
+
+    /// Parses dice in the common `NdS` notation, e.g. `2d6`.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (count, sides) = text.trim().split_once(['d', 'D'])?;
+        let count = count.parse().ok()?;
+        let sides = sides.parse().ok()?;
+        if count == 0 || sides == 0 {
+            return None;
+        }
+
+        Some(Self { count, sides })
+    }
+
+    /// Returns `true` if no dice are rolled.
+    pub fn is_none(&self) -> bool {
+        self.count == 0 || self.sides == 0
+    }
+
+    /// Gets the highest possible roll.
+    pub fn max(&self) -> u32 {
+        if self.is_none() {
+            0
+        } else {
+            self.count * self.sides
+        }
+    }
+
+    /// Enumerates all possible sums of a roll along with their probabilities.
+    pub fn distribution(&self) -> Vec<(u32, f32)> {
+        if self.is_none() {
+            return vec![(0, 1.0)];
+        }
+
+        // The probabilities of each sum, indexed by the sum, after rolling each die in turn.
+        let sides = self.sides as usize;
+        let mut sums = vec![1.0f64];
+        for _ in 0..self.count {
+            let mut next = vec![0.0; sums.len() + sides];
+            for (sum, probability) in sums.iter().enumerate() {
+                for face in 1..=sides {
+                    next[sum + face] += probability / sides as f64;
+                }
+            }
+            sums = next;
+        }
+
+        sums.into_iter()
+            .enumerate()
+            .filter(|(_, probability)| *probability > 0.0)
+            .map(|(sum, probability)| (sum as u32, probability as f32))
+            .collect()
+    }
+}
+
+impl Display for Dice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}d{}", self.count, self.sides)
+    }
+}
+
+/// Weapons compare equal if all of their fields are equal;
+/// damage values and chances are never `NaN`.
+impl Eq for Weapon {}
+
+impl Hash for Weapon {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.damage.to_bits().hash(state);
+        self.dice.hash(state);
+        self.damage_type.hash(state);
+        self.range.hash(state);
+        self.area.hash(state);
+        self.hit_chance.to_bits().hash(state);
+        self.critical_chance.to_bits().hash(state);
+        self.effects.hash(state);
+        self.cost.hash(state);
+    }
 }
 
 impl Debug for Weapon {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Weapon::Stick(item) => write!(
-                f,
-                "a stick{}",
-                if f.alternate() {
-                    format!(" ({} damage)", item.damage)
-                } else {
-                    String::default()
-                }
-            ),
-            Weapon::Fists(item) => write!(
-                f,
-                "their fists{}",
-                if f.alternate() {
-                    format!(" ({} damage)", item.damage)
-                } else {
-                    String::default()
-                }
-            ),
+        if !f.alternate() {
+            return write!(f, "{}", self.name);
+        }
+
+        let mut details = vec![format!("{}{} damage", self.damage, dice_suffix(self.dice))];
+        if self.damage_type != DamageType::default() {
+            details.push(format!("{:?}", self.damage_type).to_lowercase());
+        }
+        if self.range != WeaponRange::default() {
+            details.push(format!("{:?}", self.range).to_lowercase());
+        }
+        match self.area {
+            WeaponArea::Single => {}
+            WeaponArea::Cleave => details.push("cleaving".to_string()),
+            WeaponArea::All => details.push("hitting all opponents".to_string()),
+        }
+        if self.hit_chance < 1.0 {
+            details.push(format!("{}% to hit", self.hit_chance * 100.0));
+        }
+        if self.critical_chance > 0.0 {
+            details.push(format!("{}% critical", self.critical_chance * 100.0));
         }
+        for effect in &self.effects {
+            details.push(format!("{:?}", effect));
+        }
+        if !self.cost.is_free() {
+            details.push(format!("costs {}", self.cost));
+        }
+
+        write!(f, "{} ({})", self.name, details.join(", "))
+    }
+}
+
+/// Formats the dice to add to a base damage, if any.
+fn dice_suffix(dice: Dice) -> String {
+    if dice.is_none() {
+        String::default()
+    } else {
+        format!("+{dice}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dice_distribution_works() {
+        let dice = Dice::parse("2d6").unwrap();
+        let distribution = dice.distribution();
+
+        assert_eq!(distribution.len(), 11);
+        assert_eq!(distribution[0], (2, 1.0 / 36.0));
+        assert_eq!(distribution[5], (7, 6.0 / 36.0));
+        let total: f32 = distribution.iter().map(|(_, p)| p).sum();
+        assert!((total - 1.0).abs() < 1e-6);
+
+        assert_eq!(Dice::NONE.distribution(), vec![(0, 1.0)]);
+        assert_eq!(Dice::parse("0d6"), None);
+        assert_eq!(Dice::parse("d6"), None);
     }
 }